@@ -1,13 +1,148 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+use crate::core::{Format, PageRef, Source};
 
 #[derive(Parser, Debug)]
-#[command(version, about, long_about = None)]
+#[command(
+    version,
+    about,
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    after_help = "To open a page named like a subcommand, give its section, e.g. `mantui 'export(1p)'`, \
+or put it after `--`, e.g. `mantui -- export`.\n\n\
+The subcommands exit with 0 if pages were found, 1 if nothing was found, 2 on usage errors \
+and 3 if `man` failed."
+)]
 pub struct Args {
-    /// Show the man page of a command.
-    #[arg(value_name = "COMMAND", help = "Show the man page of a command.")]
-    pub(crate) command: Option<String>,
+    /// Show the man pages of commands or man page files, each in its own tab.
+    #[arg(
+        value_name = "COMMAND",
+        help = "Show the man pages of commands, e.g. `tar` or `ssh_config(5)`, or man page files, e.g. `./docs/foo.1`, each in its own tab."
+    )]
+    pub(crate) commands: Vec<String>,
+
+    /// Render man page files again whenever they change.
+    #[arg(long, requires = "commands")]
+    pub(crate) watch: bool,
+
+    /// Print the pages to stdout instead of opening the reader.
+    #[arg(short, long, requires = "commands")]
+    pub(crate) print: bool,
+
+    /// The width to print at. Defaults to the width of the terminal, or 80.
+    #[arg(long, value_name = "COLUMNS", requires = "print")]
+    pub(crate) width: Option<u16>,
+
+    /// Only print these sections, e.g. `SYNOPSIS,OPTIONS`.
+    #[arg(
+        long,
+        value_name = "HEADINGS",
+        value_delimiter = ',',
+        requires = "print"
+    )]
+    pub(crate) section: Vec<String>,
+
+    /// Compare two man pages or man page files, e.g. `printf(1) printf(1p)`.
+    #[arg(long, num_args = 2, value_names = ["OLD", "NEW"], conflicts_with = "commands")]
+    pub(crate) diff: Option<Vec<Source>>,
 
     /// Use a transparent background.
     #[arg(short, long)]
     pub transparent: bool,
+
+    /// The color theme, either built-in (dark, light, high-contrast, solarized)
+    /// or the name of a file in the themes directory.
+    #[arg(long, value_name = "NAME")]
+    pub theme: Option<String>,
+
+    #[command(subcommand)]
+    pub(crate) subcommand: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum Command {
+    /// Import or export the page collections.
+    Collections {
+        #[command(subcommand)]
+        action: CollectionsAction,
+    },
+    /// List the pages of a section, or of all sections.
+    List {
+        /// The section, e.g. `3`.
+        #[arg(short, long)]
+        section: Option<String>,
+
+        /// Print JSON instead of tab-separated values.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Search the names and descriptions of the pages, like `man -k`.
+    Search {
+        /// A regular expression.
+        query: String,
+
+        /// Only search a section, e.g. `3`.
+        #[arg(short, long)]
+        section: Option<String>,
+
+        /// Print JSON instead of tab-separated values.
+        #[arg(long)]
+        json: bool,
+    },
+    /// List the man sections.
+    Sections {
+        /// Print JSON instead of tab-separated values.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Export a man page to Markdown, HTML or plain text.
+    Export {
+        /// The page, e.g. `tar` or `ssh_config(5)`.
+        page: PageRef,
+
+        /// The format: md, html or txt. Defaults to the extension of the output file, or txt.
+        #[arg(short, long, value_name = "FORMAT")]
+        format: Option<Format>,
+
+        /// Write to a file instead of printing.
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum CollectionsAction {
+    /// Print the collections as plain text, or write them to a file.
+    Export {
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Add the pages of a plain text file to the collections.
+    Import { file: PathBuf },
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_args() {
+        Args::command().debug_assert();
+    }
+
+    #[test]
+    fn test_page_named_like_subcommand() {
+        let args = Args::parse_from(["mantui", "--", "export"]);
+        assert!(args.subcommand.is_none());
+        assert_eq!(args.commands, ["export"]);
+
+        let args = Args::parse_from(["mantui", "export(1p)"]);
+        assert!(args.subcommand.is_none());
+        assert_eq!(args.commands, ["export(1p)"]);
+
+        let args = Args::parse_from(["mantui", "export", "tar"]);
+        assert!(matches!(args.subcommand, Some(Command::Export { .. })));
+    }
 }
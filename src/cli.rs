@@ -0,0 +1,118 @@
+use anyhow::{Context, Result};
+use serde_json::json;
+use std::{fs, path::Path, process::ExitCode};
+
+use crate::args::{CollectionsAction, Command};
+use crate::core::{
+    export_page, list_pages, search_pages, Collections, Entry, Format, PageRef, SECTIONS,
+};
+
+/// The exit status if a listing or search found nothing, like `grep`.
+const NO_RESULTS: u8 = 1;
+
+/// The exit status if a command failed, e.g. because `man` could not be run.
+/// Usage errors exit with `2`, which clap reports.
+const FAILURE: u8 = 3;
+
+/// Runs a command without starting the terminal UI.
+pub(crate) fn run(command: Command) -> ExitCode {
+    let result = match command {
+        Command::Collections { action } => collections(action).map(|()| ExitCode::SUCCESS),
+        Command::List { section, json } => print_entries(list_pages(section.as_deref()), json),
+        Command::Search {
+            query,
+            section,
+            json,
+        } => print_entries(search_pages(&query, section.as_deref()), json),
+        Command::Sections { json } => sections(json).map(|()| ExitCode::SUCCESS),
+        Command::Export {
+            page,
+            format,
+            output,
+        } => export(&page, format, output.as_deref()).map(|()| ExitCode::SUCCESS),
+    };
+
+    result.unwrap_or_else(|err| {
+        eprintln!("Error: {err:#}");
+        ExitCode::from(FAILURE)
+    })
+}
+
+/// Prints pages as tab-separated name, section, description and
+/// source file, or as JSON.
+fn print_entries(entries: Result<Vec<Entry>>, json: bool) -> Result<ExitCode> {
+    let entries = entries?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+    } else {
+        for entry in &entries {
+            let path = entry.path.as_ref().map(|path| path.display().to_string());
+            println!(
+                "{}\t{}\t{}\t{}",
+                entry.name,
+                entry.section,
+                entry.description,
+                path.unwrap_or_default()
+            );
+        }
+    }
+
+    if entries.is_empty() {
+        Ok(ExitCode::from(NO_RESULTS))
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+fn sections(json: bool) -> Result<()> {
+    if json {
+        let sections: Vec<_> = SECTIONS
+            .iter()
+            .map(|(section, title)| json!({ "section": section, "title": title }))
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&sections)?);
+    } else {
+        for (section, title) in SECTIONS {
+            println!("{section}\t{title}");
+        }
+    }
+    Ok(())
+}
+
+fn collections(action: CollectionsAction) -> Result<()> {
+    let mut collections = Collections::load()?;
+
+    match action {
+        CollectionsAction::Export { output: Some(path) } => {
+            fs::write(&path, collections.export())
+                .with_context(|| format!("failed to write {}", path.display()))?;
+        }
+        CollectionsAction::Export { output: None } => print!("{}", collections.export()),
+        CollectionsAction::Import { file } => {
+            let text = fs::read_to_string(&file)
+                .with_context(|| format!("failed to read {}", file.display()))?;
+            let added = collections.import(&text)?;
+            collections.save()?;
+            println!("Imported {added} pages");
+        }
+    }
+
+    Ok(())
+}
+
+fn export(page: &PageRef, format: Option<Format>, output: Option<&Path>) -> Result<()> {
+    let format = format
+        .or_else(|| output.and_then(Format::from_path))
+        .unwrap_or(Format::Text);
+    let (_, text) = export_page(page, format)?;
+
+    match output {
+        Some(path) => {
+            fs::write(path, text).with_context(|| format!("failed to write {}", path.display()))?
+        }
+        None => print!("{text}"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::{env, fs, path::PathBuf};
+
+use crate::core::HelpFallback;
+use crate::ui::{ClipboardConfig, ColorSupport, StatusField};
+
+/// The user configuration, read from `config.toml` in the config directory.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub(crate) struct Config {
+    /// The name of the color theme.
+    pub(crate) theme: Option<String>,
+
+    /// Overrides the detected color support of the terminal.
+    pub(crate) colors: Option<ColorSupport>,
+
+    /// Showing the `--help` output of commands without a man page.
+    pub(crate) help_fallback: HelpFallback,
+
+    /// The fields of the status line in the reader, all of them by default.
+    pub(crate) status_bar: Option<Vec<StatusField>>,
+
+    /// How selected text is copied.
+    pub(crate) clipboard: ClipboardConfig,
+}
+
+impl Config {
+    /// Reads the configuration, but starts with the default one if it can't
+    /// be read, e.g. because of a syntax error, which is returned as well.
+    pub(crate) fn load() -> (Self, Option<anyhow::Error>) {
+        match Self::read() {
+            Ok(config) => (config, None),
+            Err(err) => (Self::default(), Some(err)),
+        }
+    }
+
+    fn read() -> Result<Self> {
+        let Some(path) = config_dir().map(|dir| dir.join("config.toml")) else {
+            return Ok(Self::default());
+        };
+
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(&path)?;
+        toml::from_str(&content).map_err(|err| {
+            // The error is shown as a notification, thus on one line instead
+            // of quoting the line with the error.
+            let line = err
+                .span()
+                .map_or(1, |span| content[..span.start].matches('\n').count() + 1);
+            anyhow!(
+                "failed to parse {} at line {line}: {}",
+                path.display(),
+                err.message()
+            )
+        })
+    }
+}
+
+/// Returns the config directory, i.e. `$XDG_CONFIG_HOME/mantui` or `~/.config/mantui`.
+pub(crate) fn config_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("mantui"))
+}
+
+/// Returns the data directory, i.e. `$XDG_DATA_HOME/mantui` or `~/.local/share/mantui`.
+pub(crate) fn data_dir() -> Option<PathBuf> {
+    env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .map(|dir| dir.join("mantui"))
+}
@@ -0,0 +1,232 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use super::page::PageRef;
+use super::store;
+
+/// The collection that starred pages are added to.
+pub(crate) const FAVORITES: &str = "Favorites";
+
+/// Named collections of man pages, persisted in the data directory.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct Collections {
+    #[serde(default, rename = "collection")]
+    collections: Vec<Collection>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Collection {
+    pub(crate) name: String,
+    #[serde(default)]
+    pub(crate) pages: Vec<PageRef>,
+}
+
+impl Collections {
+    const FILE: &str = "collections.toml";
+
+    pub(crate) fn load() -> Result<Self> {
+        store::load(Self::FILE)
+    }
+
+    /// Loads the collections, or starts without any if the file is broken.
+    pub(crate) fn load_or_default() -> (Self, Option<anyhow::Error>) {
+        store::load_or_default(Self::FILE)
+    }
+
+    pub(crate) fn save(&self) -> Result<()> {
+        store::save(Self::FILE, self)
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Collection> {
+        self.collections.iter()
+    }
+
+    pub(crate) fn get(&self, index: usize) -> Option<&Collection> {
+        self.collections.get(index)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.collections.len()
+    }
+
+    fn find(&self, name: &str) -> Option<&Collection> {
+        self.collections.iter().find(|c| c.name == name)
+    }
+
+    fn find_mut(&mut self, name: &str) -> Option<&mut Collection> {
+        self.collections.iter_mut().find(|c| c.name == name)
+    }
+
+    pub(crate) fn contains(&self, collection: &str, page: &PageRef) -> bool {
+        self.find(collection)
+            .is_some_and(|collection| collection.pages.contains(page))
+    }
+
+    /// Adds a page to a collection, creating the collection if it does not exist.
+    pub(crate) fn add(&mut self, collection: &str, page: PageRef) {
+        if self.find(collection).is_none() {
+            self.collections.push(Collection {
+                name: collection.to_string(),
+                pages: Vec::new(),
+            });
+        }
+
+        let collection = self.find_mut(collection).unwrap();
+        if !collection.pages.contains(&page) {
+            collection.pages.push(page);
+        }
+    }
+
+    pub(crate) fn remove(&mut self, collection: &str, page: &PageRef) {
+        if let Some(collection) = self.find_mut(collection) {
+            collection.pages.retain(|p| p != page);
+        }
+    }
+
+    /// Adds a page to a collection or removes it if it is already contained.
+    /// Returns whether the page is part of the collection afterwards.
+    pub(crate) fn toggle(&mut self, collection: &str, page: PageRef) -> bool {
+        if self.contains(collection, &page) {
+            self.remove(collection, &page);
+            false
+        } else {
+            self.add(collection, page);
+            true
+        }
+    }
+
+    pub(crate) fn create(&mut self, name: &str) -> Result<()> {
+        let name = validate_name(name)?;
+        if self.find(name).is_some() {
+            return Err(anyhow!("collection {name} already exists"));
+        }
+
+        self.collections.push(Collection {
+            name: name.to_string(),
+            pages: Vec::new(),
+        });
+        Ok(())
+    }
+
+    pub(crate) fn rename(&mut self, old: &str, new: &str) -> Result<()> {
+        let new = validate_name(new)?;
+        if old != new && self.find(new).is_some() {
+            return Err(anyhow!("collection {new} already exists"));
+        }
+
+        let collection = self
+            .find_mut(old)
+            .ok_or_else(|| anyhow!("unknown collection: {old}"))?;
+        collection.name = new.to_string();
+        Ok(())
+    }
+
+    pub(crate) fn delete(&mut self, name: &str) {
+        self.collections.retain(|c| c.name != name);
+    }
+
+    /// Formats the collections as plain text, one page per line
+    /// below the name of its collection in brackets:
+    ///
+    /// ```text
+    /// [networking]
+    /// ssh_config(5)
+    /// rsync(1)
+    /// ```
+    pub(crate) fn export(&self) -> String {
+        let mut text = String::new();
+        for collection in &self.collections {
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text.push_str(&format!("[{}]\n", collection.name));
+            for page in &collection.pages {
+                text.push_str(&format!("{page}\n"));
+            }
+        }
+        text
+    }
+
+    /// Merges collections in the format of [`Collections::export`]. Pages before
+    /// the first collection name are added to the favorites. Lines starting
+    /// with `#` are ignored. Returns the number of added pages.
+    pub(crate) fn import(&mut self, text: &str) -> Result<usize> {
+        let mut collection = FAVORITES.to_string();
+        let mut added = 0;
+
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                collection = validate_name(name)?.to_string();
+                if self.find(&collection).is_none() {
+                    self.create(&collection)?;
+                }
+                continue;
+            }
+
+            let page: PageRef = line.parse()?;
+            if !self.contains(&collection, &page) {
+                self.add(&collection, page);
+                added += 1;
+            }
+        }
+
+        Ok(added)
+    }
+}
+
+fn validate_name(name: &str) -> Result<&str> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(anyhow!("the collection name must not be empty"));
+    }
+    if name.contains(['[', ']']) {
+        return Err(anyhow!("the collection name must not contain brackets"));
+    }
+    Ok(name)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn page(s: &str) -> PageRef {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_toggle() {
+        let mut collections = Collections::default();
+
+        assert!(collections.toggle(FAVORITES, page("jq(1)")));
+        assert!(collections.contains(FAVORITES, &page("jq(1)")));
+
+        assert!(!collections.toggle(FAVORITES, page("jq(1)")));
+        assert!(!collections.contains(FAVORITES, &page("jq(1)")));
+    }
+
+    #[test]
+    fn test_export_import() {
+        let mut collections = Collections::default();
+        collections.add("networking", page("ssh_config(5)"));
+        collections.add("networking", page("rsync(1)"));
+        collections.add("oncall", page("jq"));
+
+        let text = collections.export();
+        assert_eq!(
+            text,
+            "[networking]\nssh_config(5)\nrsync(1)\n\n[oncall]\njq\n"
+        );
+
+        let mut imported = Collections::default();
+        let added = imported.import(&text).unwrap();
+        assert_eq!(added, 3);
+        assert_eq!(imported.collections, collections.collections);
+
+        // Importing again adds nothing.
+        assert_eq!(imported.import(&text).unwrap(), 0);
+    }
+}
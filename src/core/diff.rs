@@ -0,0 +1,151 @@
+use similar::{Algorithm, ChangeTag, TextDiff};
+
+/// Whether a line exists in both texts or only in one of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Change {
+    Equal,
+    Removed,
+    Added,
+}
+
+/// A line of a diff as runs of text. Runs are marked if their words
+/// changed, so that the changes within a line can be highlighted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DiffLine {
+    pub(crate) change: Change,
+    pub(crate) runs: Vec<(bool, String)>,
+}
+
+/// The line diff of two texts, with word-level changes within lines.
+#[derive(Debug, Default)]
+pub(crate) struct Diff {
+    /// The lines in the order of a unified diff.
+    pub(crate) lines: Vec<DiffLine>,
+}
+
+impl Diff {
+    pub(crate) fn new(old: &[String], new: &[String]) -> Self {
+        let old: Vec<&str> = old.iter().map(String::as_str).collect();
+        let new: Vec<&str> = new.iter().map(String::as_str).collect();
+        let diff = TextDiff::configure()
+            .algorithm(Algorithm::Patience)
+            .diff_slices(&old, &new);
+
+        let lines = diff
+            .ops()
+            .iter()
+            .flat_map(|op| diff.iter_inline_changes(op))
+            .map(|change| DiffLine {
+                change: match change.tag() {
+                    ChangeTag::Equal => Change::Equal,
+                    ChangeTag::Delete => Change::Removed,
+                    ChangeTag::Insert => Change::Added,
+                },
+                runs: change
+                    .iter_strings_lossy()
+                    .map(|(changed, text)| (changed, text.into_owned()))
+                    .collect(),
+            })
+            .collect();
+
+        Self { lines }
+    }
+
+    /// Returns whether the texts are the same.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.lines.iter().all(|line| line.change == Change::Equal)
+    }
+
+    /// Pairs the lines for a side by side view. Removed lines are shown next
+    /// to the lines added in their place, equal lines are shown on both sides.
+    pub(crate) fn side_by_side(&self) -> Vec<(Option<usize>, Option<usize>)> {
+        let mut rows = Vec::new();
+        let (mut removed, mut added) = (Vec::new(), Vec::new());
+
+        for (index, line) in self.lines.iter().enumerate() {
+            match line.change {
+                Change::Removed => removed.push(index),
+                Change::Added => added.push(index),
+                Change::Equal => {
+                    pair(&mut rows, &mut removed, &mut added);
+                    rows.push((Some(index), Some(index)));
+                }
+            }
+        }
+        pair(&mut rows, &mut removed, &mut added);
+
+        rows
+    }
+}
+
+/// Adds rows with the removed lines next to the added ones.
+fn pair(
+    rows: &mut Vec<(Option<usize>, Option<usize>)>,
+    removed: &mut Vec<usize>,
+    added: &mut Vec<usize>,
+) {
+    for i in 0..removed.len().max(added.len()) {
+        rows.push((removed.get(i).copied(), added.get(i).copied()));
+    }
+    removed.clear();
+    added.clear();
+}
+
+/// Returns the rows at which a run of changed rows starts.
+pub(crate) fn hunks(changed: impl IntoIterator<Item = bool>) -> Vec<usize> {
+    let mut previous = false;
+    changed
+        .into_iter()
+        .enumerate()
+        .filter_map(|(row, changed)| {
+            let start = changed && !previous;
+            previous = changed;
+            start.then_some(row)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = lines("NAME\n  printf - format and print data\n\nSYNOPSIS\n  printf FORMAT");
+        let new = lines("NAME\n  printf - write formatted output\n\nSYNOPSIS\n  printf FORMAT");
+        let diff = Diff::new(&old, &new);
+
+        let changes: Vec<_> = diff.lines.iter().map(|line| line.change).collect();
+        assert_eq!(
+            changes,
+            vec![
+                Change::Equal,
+                Change::Removed,
+                Change::Added,
+                Change::Equal,
+                Change::Equal,
+                Change::Equal,
+            ]
+        );
+
+        let changed: Vec<_> = diff.lines[2]
+            .runs
+            .iter()
+            .filter(|(changed, _)| *changed)
+            .map(|(_, text)| text.trim())
+            .collect();
+        assert!(changed.contains(&"formatted"));
+        assert!(!changed.contains(&"printf"));
+
+        let rows = diff.side_by_side();
+        assert_eq!(rows[1], (Some(1), Some(2)));
+        assert_eq!(rows.len(), 5);
+
+        let changed = rows.iter().map(|(l, r)| l != r);
+        assert_eq!(hunks(changed), vec![1]);
+    }
+}
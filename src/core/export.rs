@@ -0,0 +1,481 @@
+use anyhow::{anyhow, Error};
+use std::{collections::HashSet, fmt, path::Path, str::FromStr};
+
+use super::manual::{Emphasis, Fragment, Manual};
+use super::page::PageRef;
+
+/// The formats a page can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Format {
+    Markdown,
+    Html,
+    Text,
+}
+
+impl Format {
+    pub(crate) const ALL: [Format; 3] = [Format::Markdown, Format::Html, Format::Text];
+
+    pub(crate) fn extension(self) -> &'static str {
+        match self {
+            Format::Markdown => "md",
+            Format::Html => "html",
+            Format::Text => "txt",
+        }
+    }
+
+    /// Guesses the format from the extension of a file.
+    pub(crate) fn from_path(path: &Path) -> Option<Self> {
+        path.extension()?.to_str()?.parse().ok()
+    }
+
+    /// The width to render the page with. Markdown and HTML are reflowed,
+    /// thus they are rendered wide to avoid hyphenation.
+    pub(crate) fn width(self) -> &'static str {
+        match self {
+            Format::Text => "80",
+            Format::Markdown | Format::Html => "1000",
+        }
+    }
+
+    /// Returns the file name of an exported page, e.g. `tar.1.md`.
+    /// References to other pages link to these names.
+    pub(crate) fn file_name(self, page: &PageRef) -> String {
+        match &page.section {
+            Some(section) => format!("{}.{section}.{}", page.name, self.extension()),
+            None => format!("{}.{}", page.name, self.extension()),
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "md" | "markdown" => Ok(Format::Markdown),
+            "html" | "htm" => Ok(Format::Html),
+            "txt" | "text" => Ok(Format::Text),
+            _ => Err(anyhow!("unknown format `{s}`, expected md, html or txt")),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.extension())
+    }
+}
+
+/// Returns the page named in the header of a manual, e.g. `TAR(1)`.
+pub(super) fn header_page(manual: &Manual) -> Option<PageRef> {
+    let lines = manual.plain_lines();
+    let header = lines.iter().find(|line| !line.trim().is_empty())?;
+    let page: PageRef = header.split_whitespace().next()?.parse().ok()?;
+    page.section.is_some().then_some(page)
+}
+
+/// Converts a manual to a format. The output only depends on the manual
+/// and the page, so that exports can be committed and compared.
+pub(crate) fn export(manual: &Manual, page: &PageRef, format: Format) -> String {
+    match format {
+        Format::Text => text(manual),
+        Format::Markdown => markdown(&blocks(manual), page),
+        Format::Html => html(&blocks(manual), page),
+    }
+}
+
+fn text(manual: &Manual) -> String {
+    let lines = manual.plain_lines();
+    let lines: Vec<&str> = lines.iter().map(|line| line.trim_end()).collect();
+    let start = lines.iter().position(|line| !line.is_empty());
+    let end = lines.iter().rposition(|line| !line.is_empty());
+
+    match start.zip(end) {
+        Some((start, end)) => lines[start..=end].join("\n") + "\n",
+        None => String::new(),
+    }
+}
+
+/// A run of text with the same emphasis, or a reference to another page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Run {
+    text: String,
+    emphasis: Emphasis,
+    link: Option<PageRef>,
+}
+
+/// The structure of a page, independent of the output format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Block {
+    Heading(String),
+    /// Lines at the indentation of the section, joined.
+    Paragraph(Vec<Run>),
+    /// A line followed by an indented description, e.g. an option.
+    Term(Vec<Run>),
+    /// Indented lines, usually describing the term above.
+    Description(Vec<Run>),
+}
+
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Splits a manual into headings and blocks of text. The header and
+/// footer are left out, since they are not indented but no headings.
+fn blocks(manual: &Manual) -> Vec<Block> {
+    let plain = manual.plain_lines();
+    let headings: HashSet<usize> = manual.headings().into_iter().map(|(i, _)| i).collect();
+
+    // The sections, each with its heading and its lines.
+    let mut sections: Vec<(Option<usize>, Vec<usize>)> = vec![(None, Vec::new())];
+    for (index, line) in plain.iter().enumerate() {
+        if headings.contains(&index) {
+            sections.push((Some(index), Vec::new()));
+        } else if line.starts_with(char::is_whitespace) || line.is_empty() {
+            sections.last_mut().unwrap().1.push(index);
+        }
+    }
+
+    let mut blocks = Vec::new();
+    for (heading, lines) in sections {
+        if let Some(heading) = heading {
+            blocks.push(Block::Heading(plain[heading].trim().to_string()));
+        }
+
+        let base = lines
+            .iter()
+            .filter(|index| !plain[**index].trim().is_empty())
+            .map(|index| indent(&plain[*index]))
+            .min()
+            .unwrap_or_default();
+
+        for group in lines.split(|index| plain[*index].trim().is_empty()) {
+            let join = |lines: &[usize]| {
+                let lines: Vec<&[Fragment]> = lines.iter().map(|i| &manual.lines[*i][..]).collect();
+                runs(&lines)
+            };
+
+            let mut i = 0;
+            while i < group.len() {
+                let deeper = |index: &usize| indent(&plain[*index]) > base;
+                let len = group[i..]
+                    .iter()
+                    .take_while(|index| deeper(index) == deeper(&group[i]));
+                let end = i + len.count();
+
+                if deeper(&group[i]) {
+                    blocks.push(Block::Description(join(&group[i..end])));
+                } else if end < group.len() {
+                    // The last line before indented lines is a term.
+                    if end - 1 > i {
+                        blocks.push(Block::Paragraph(join(&group[i..end - 1])));
+                    }
+                    blocks.push(Block::Term(join(&group[end - 1..end])));
+                } else {
+                    blocks.push(Block::Paragraph(join(&group[i..end])));
+                }
+                i = end;
+            }
+        }
+    }
+
+    blocks
+}
+
+/// Joins lines into runs with collapsed whitespace and
+/// turns references like `ls(1)` into links.
+fn runs(lines: &[&[Fragment]]) -> Vec<Run> {
+    let mut chars: Vec<(char, Emphasis)> = Vec::new();
+    for line in lines {
+        chars.push((' ', Emphasis::None));
+        for fragment in *line {
+            chars.extend(fragment.text.chars().map(|ch| (ch, fragment.emphasis)));
+        }
+    }
+
+    // Collapse whitespace.
+    let mut collapsed: Vec<(char, Emphasis)> = Vec::new();
+    for (ch, emphasis) in chars {
+        if ch.is_whitespace() {
+            if collapsed.last().is_some_and(|(last, _)| *last != ' ') {
+                collapsed.push((' ', Emphasis::None));
+            }
+        } else {
+            collapsed.push((ch, emphasis));
+        }
+    }
+    if collapsed.last().is_some_and(|(last, _)| *last == ' ') {
+        collapsed.pop();
+    }
+
+    let text: Vec<char> = collapsed.iter().map(|(ch, _)| *ch).collect();
+    let mut runs: Vec<Run> = Vec::new();
+    let mut i = 0;
+    while i < collapsed.len() {
+        if let Some((end, page)) = reference(&text, i) {
+            runs.push(Run {
+                text: text[i..end].iter().collect(),
+                emphasis: Emphasis::None,
+                link: Some(page),
+            });
+            i = end;
+            continue;
+        }
+
+        let (ch, emphasis) = collapsed[i];
+        match runs.last_mut() {
+            Some(run) if run.link.is_none() && run.emphasis == emphasis => run.text.push(ch),
+            _ => runs.push(Run {
+                text: ch.to_string(),
+                emphasis,
+                link: None,
+            }),
+        }
+        i += 1;
+    }
+
+    runs
+}
+
+/// Returns the end and the page if a reference like `ls(1)` starts at `start`.
+fn reference(text: &[char], start: usize) -> Option<(usize, PageRef)> {
+    let is_name = |ch: &char| ch.is_alphanumeric() || matches!(ch, '_' | '-' | '.' | ':' | '+');
+
+    // References start at a word boundary.
+    if start > 0 && is_name(&text[start - 1]) {
+        return None;
+    }
+    if !text[start].is_alphanumeric() && text[start] != '_' {
+        return None;
+    }
+
+    let open = start + text[start..].iter().take_while(|ch| is_name(ch)).count();
+    if text.get(open) != Some(&'(') || !text.get(open + 1)?.is_ascii_digit() {
+        return None;
+    }
+    let section_len = text[open + 1..]
+        .iter()
+        .take_while(|ch| ch.is_ascii_alphanumeric())
+        .count();
+    let close = open + 1 + section_len;
+    if text.get(close) != Some(&')') || section_len > 4 {
+        return None;
+    }
+
+    let name: String = text[start..open].iter().collect();
+    let section: String = text[open + 1..close].iter().collect();
+    Some((close + 1, PageRef::new(&name, Some(&section))))
+}
+
+fn markdown(blocks: &[Block], page: &PageRef) -> String {
+    let mut parts = vec![format!("# {}", escape_markdown(&page.to_string()))];
+    let mut in_list = false;
+
+    for block in blocks {
+        let part = match block {
+            Block::Heading(heading) => {
+                in_list = false;
+                format!("## {}", escape_markdown(heading))
+            }
+            Block::Paragraph(runs) => {
+                in_list = false;
+                markdown_runs(runs)
+            }
+            Block::Term(runs) => {
+                in_list = true;
+                format!("- {}", markdown_runs(runs))
+            }
+            Block::Description(runs) if in_list => format!("  {}", markdown_runs(runs)),
+            Block::Description(runs) => markdown_runs(runs),
+        };
+        parts.push(part);
+    }
+
+    parts.join("\n\n") + "\n"
+}
+
+fn markdown_runs(runs: &[Run]) -> String {
+    let mut text = String::new();
+    for run in runs {
+        if let Some(page) = &run.link {
+            let target = Format::Markdown.file_name(page);
+            text.push_str(&format!("[{}]({target})", escape_markdown(&run.text)));
+            continue;
+        }
+
+        let marker = match run.emphasis {
+            Emphasis::None => "",
+            Emphasis::Bold => "**",
+            Emphasis::Italic | Emphasis::Underline | Emphasis::ItalicUnderline => "*",
+            Emphasis::BoldUnderline | Emphasis::BoldItalic | Emphasis::BoldItalicUnderline => "***",
+        };
+        let trimmed = run.text.trim();
+        if marker.is_empty() || trimmed.is_empty() {
+            text.push_str(&escape_markdown(&run.text));
+            continue;
+        }
+
+        // Markdown emphasis must not start or end with whitespace.
+        let leading = &run.text[..run.text.len() - run.text.trim_start().len()];
+        let trailing = &run.text[run.text.trim_end().len()..];
+        let trimmed = escape_markdown(trimmed);
+        text.push_str(&format!("{leading}{marker}{trimmed}{marker}{trailing}"));
+    }
+
+    // Text that starts like a list or a heading is escaped.
+    let digits = text.chars().take_while(char::is_ascii_digit).count();
+    if text.starts_with(['-', '+', '#', '>']) {
+        text.insert(0, '\\');
+    } else if digits > 0 && text[digits..].starts_with(['.', ')']) {
+        text.insert(digits, '\\');
+    }
+
+    text
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if matches!(ch, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|') {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+fn html(blocks: &[Block], page: &PageRef) -> String {
+    let title = escape_html(&page.to_string());
+    let mut lines = vec![
+        String::from("<!DOCTYPE html>"),
+        String::from("<html>"),
+        String::from("<head>"),
+        String::from("<meta charset=\"utf-8\">"),
+        format!("<title>{title}</title>"),
+        String::from("</head>"),
+        String::from("<body>"),
+        format!("<h1>{title}</h1>"),
+    ];
+
+    let mut in_list = false;
+    for block in blocks {
+        let is_list = matches!(block, Block::Term(_) | Block::Description(_));
+        if is_list && !in_list {
+            lines.push(String::from("<dl>"));
+        } else if !is_list && in_list {
+            lines.push(String::from("</dl>"));
+        }
+        in_list = is_list;
+
+        lines.push(match block {
+            Block::Heading(heading) => format!("<h2>{}</h2>", escape_html(heading)),
+            Block::Paragraph(runs) => format!("<p>{}</p>", html_runs(runs)),
+            Block::Term(runs) => format!("<dt>{}</dt>", html_runs(runs)),
+            Block::Description(runs) => format!("<dd>{}</dd>", html_runs(runs)),
+        });
+    }
+    if in_list {
+        lines.push(String::from("</dl>"));
+    }
+
+    lines.push(String::from("</body>"));
+    lines.push(String::from("</html>"));
+    lines.join("\n") + "\n"
+}
+
+fn html_runs(runs: &[Run]) -> String {
+    runs.iter()
+        .map(|run| {
+            let text = escape_html(&run.text);
+            if let Some(page) = &run.link {
+                let target = escape_html(&Format::Html.file_name(page));
+                return format!("<a href=\"{target}\">{text}</a>");
+            }
+            match run.emphasis {
+                Emphasis::None => text,
+                Emphasis::Bold => format!("<b>{text}</b>"),
+                Emphasis::Italic | Emphasis::Underline | Emphasis::ItalicUnderline => {
+                    format!("<i>{text}</i>")
+                }
+                Emphasis::BoldUnderline | Emphasis::BoldItalic | Emphasis::BoldItalicUnderline => {
+                    format!("<b><i>{text}</i></b>")
+                }
+            }
+        })
+        .collect()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn manual() -> Manual {
+        let mut manual = Manual::plain("TAR(1)     User Commands     TAR(1)\n\nNAME\n");
+        manual.lines.push(Vec::new());
+        manual.push("       ", Emphasis::None);
+        manual.push("tar", Emphasis::Bold);
+        manual.push(" - an archiving utility, see gzip(1)", Emphasis::None);
+        for line in [
+            "",
+            "OPTIONS",
+            "       -c, --create",
+            "              Create a new",
+            "              archive.",
+            "",
+            "GNU tar     TAR(1)",
+        ] {
+            manual.lines.push(Vec::new());
+            if !line.is_empty() {
+                manual.push(line, Emphasis::None);
+            }
+        }
+        manual
+    }
+
+    #[test]
+    fn test_markdown() {
+        let page = PageRef::new("tar", Some("1"));
+        let expected = "# tar(1)\n\n## NAME\n\n**tar** - an archiving utility, see [gzip(1)](gzip.1.md)\n\n## OPTIONS\n\n- \\-c, --create\n\n  Create a new archive.\n";
+        assert_eq!(export(&manual(), &page, Format::Markdown), expected);
+    }
+
+    #[test]
+    fn test_html() {
+        let page = PageRef::new("tar", Some("1"));
+        let html = export(&manual(), &page, Format::Html);
+
+        assert!(html.contains(
+            "<p><b>tar</b> - an archiving utility, see <a href=\"gzip.1.html\">gzip(1)</a></p>"
+        ));
+        assert!(html.contains("<dl>\n<dt>-c, --create</dt>\n<dd>Create a new archive.</dd>\n</dl>"));
+        assert!(!html.contains("GNU tar"));
+    }
+
+    #[test]
+    fn test_text() {
+        let page = PageRef::new("tar", Some("1"));
+        let text = export(&manual(), &page, Format::Text);
+
+        assert!(text.starts_with("TAR(1)"));
+        assert!(text.ends_with("GNU tar     TAR(1)\n"));
+        assert_eq!(header_page(&manual()), Some(PageRef::new("TAR", Some("1"))));
+    }
+
+    #[test]
+    fn test_reference() {
+        let text: Vec<char> = "see systemd.unit(5), not f(x) or 3(1)".chars().collect();
+        assert_eq!(
+            reference(&text, 4),
+            Some((19, PageRef::new("systemd.unit", Some("5"))))
+        );
+        assert_eq!(reference(&text, 26), None);
+        assert_eq!(reference(&text, 5), None);
+    }
+}
@@ -0,0 +1,289 @@
+use anyhow::{anyhow, bail, Result};
+use serde::Deserialize;
+use std::{
+    env,
+    io::{self, Read},
+    path::Path,
+    process::{Command, Stdio},
+    sync::{mpsc, RwLock},
+    thread,
+    time::{Duration, Instant},
+};
+
+use super::manual::{Emphasis, Manual};
+
+/// Showing the `--help` output of commands without a man page, configured
+/// in the `[help-fallback]` table of the config file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub(crate) struct HelpFallback {
+    pub(crate) enabled: bool,
+    /// How long a command may run, in milliseconds.
+    pub(crate) timeout_ms: u64,
+    /// Commands that are never run, e.g. because they ignore `--help`.
+    pub(crate) never_run: Vec<String>,
+}
+
+impl Default for HelpFallback {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            timeout_ms: 2000,
+            never_run: ["halt", "poweroff", "reboot", "shutdown"]
+                .map(String::from)
+                .to_vec(),
+        }
+    }
+}
+
+static CONFIG: RwLock<Option<HelpFallback>> = RwLock::new(None);
+
+/// Replaces the configuration of the `--help` fallback.
+pub(crate) fn configure(config: HelpFallback) {
+    *CONFIG.write().unwrap() = Some(config);
+}
+
+/// Runs `help <name>` for shell builtins, else `<name> --help` or `<name> -h`,
+/// and converts the output to a manual that says where it comes from.
+/// Returns the command that printed the help along with the manual.
+pub(crate) fn read(name: &str, width: &str) -> Result<(String, Manual)> {
+    let config = CONFIG.read().unwrap().clone().unwrap_or_default();
+    if !config.enabled {
+        bail!("the --help fallback is disabled");
+    }
+    if name.contains('/') || config.never_run.iter().any(|command| command == name) {
+        bail!("{name} is never run for its --help output");
+    }
+
+    let timeout = Duration::from_millis(config.timeout_ms);
+    // The name is passed as an argument, such that the shell never parses it.
+    let (command, output) = if is_builtin(name, timeout) {
+        let script = r#"help -- "$1""#;
+        (
+            format!("help {name}"),
+            run("bash", &["-c", script, "_", name], width, timeout)?,
+        )
+    } else if in_path(name) {
+        let output = run(name, &["--help"], width, timeout);
+        match output {
+            Ok(output) => (format!("{name} --help"), output),
+            Err(_) => (format!("{name} -h"), run(name, &["-h"], width, timeout)?),
+        }
+    } else {
+        bail!("no such command: {name}");
+    };
+
+    let manual = format(name, &command, &output);
+    Ok((command, manual))
+}
+
+/// Whether a name is a shell builtin, e.g. `cd`.
+fn is_builtin(name: &str, timeout: Duration) -> bool {
+    let script = r#"type -t -- "$1""#;
+    run("bash", &["-c", script, "_", name], "80", timeout)
+        .is_ok_and(|output| output.trim() == "builtin")
+}
+
+fn in_path(name: &str) -> bool {
+    env::var_os("PATH")
+        .is_some_and(|path| env::split_paths(&path).any(|dir| Path::new(&dir).join(name).is_file()))
+}
+
+/// Runs a command without stdin and returns its output, or its error output if
+/// it printed nothing else. The command is killed if it runs too long.
+fn run(program: &str, args: &[&str], width: &str, timeout: Duration) -> Result<String> {
+    let mut child = Command::new(program)
+        .args(args)
+        .env("COLUMNS", width)
+        .env("NO_COLOR", "1")
+        .env("TERM", "dumb")
+        .env("LC_ALL", "C")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Read in threads, such that a long output doesn't block the command.
+    let stdout = read_in_background(child.stdout.take().unwrap());
+    let stderr = read_in_background(child.stderr.take().unwrap());
+
+    let start = Instant::now();
+    while child.try_wait()?.is_none() {
+        if start.elapsed() > timeout {
+            child.kill()?;
+            child.wait()?;
+            bail!("{program} did not finish within {}ms", timeout.as_millis());
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    // A process that the command started in the background may keep the
+    // output open, thus the timeout applies to reading it as well.
+    let receive = |output: mpsc::Receiver<io::Result<Vec<u8>>>| {
+        output
+            .recv_timeout(timeout.saturating_sub(start.elapsed()))
+            .map_err(|_| anyhow!("{program} did not close its output"))
+    };
+    let stdout = receive(stdout)??;
+    let stderr = receive(stderr)??;
+    // Many tools exit with an error after printing their usage, thus only the output counts.
+    let output = if stdout.iter().all(u8::is_ascii_whitespace) {
+        stderr
+    } else {
+        stdout
+    };
+    if output.iter().all(u8::is_ascii_whitespace) {
+        bail!("{program} printed no help");
+    }
+    Ok(String::from_utf8_lossy(&output).to_string())
+}
+
+/// Reads the output of a command in a thread and sends it once it is closed.
+fn read_in_background(
+    mut output: impl Read + Send + 'static,
+) -> mpsc::Receiver<io::Result<Vec<u8>>> {
+    let (sx, received) = mpsc::channel();
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        let _ = sx.send(output.read_to_end(&mut bytes).map(|_| bytes));
+    });
+    received
+}
+
+/// Converts help output to a manual. Lines like `Options:` become headings,
+/// the options at the start of a line are bold, and the first lines name the
+/// command that printed it.
+fn format(name: &str, command: &str, output: &str) -> Manual {
+    let mut manual = Manual::default();
+    manual.push(command, Emphasis::Bold);
+    manual.lines.push(Vec::new());
+    push(
+        &mut manual,
+        &format!("       There is no man page for {name}, this is the output of `{command}`."),
+        Emphasis::Italic,
+    );
+    manual.lines.push(Vec::new());
+
+    for line in strip_escapes(output).lines().map(str::trim_end) {
+        manual.lines.push(Vec::new());
+        let text = line.trim_start();
+        if line == text && text.ends_with(':') {
+            push(&mut manual, text, Emphasis::Bold);
+            continue;
+        }
+
+        push(&mut manual, "       ", Emphasis::None);
+        let indent = &line[..line.len() - text.len()];
+        push(&mut manual, indent, Emphasis::None);
+        if is_option(text) {
+            // The option ends where its description starts.
+            let end = text
+                .find("  ")
+                .or_else(|| text.find('\t'))
+                .unwrap_or(text.len());
+            push(&mut manual, &text[..end], Emphasis::Bold);
+            push(&mut manual, &text[end..], Emphasis::None);
+        } else {
+            push(&mut manual, text, Emphasis::None);
+        }
+    }
+    manual
+}
+
+/// Whether a line starts with an option like `-a` or `--all`.
+fn is_option(text: &str) -> bool {
+    let rest = text.trim_start_matches('-');
+    let dashes = text.len() - rest.len();
+    (1..=2).contains(&dashes) && rest.starts_with(|ch: char| ch.is_ascii_alphanumeric())
+}
+
+/// Appends text to the last line, empty text leaves the line as it is.
+fn push(manual: &mut Manual, text: &str, emphasis: Emphasis) {
+    if !text.is_empty() {
+        manual.push(text, emphasis);
+    }
+}
+
+/// Removes color escape sequences, e.g. `\x1b[1m`, which some tools print anyway.
+fn strip_escapes(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\x1b' {
+            result.push(ch);
+            continue;
+        }
+        if chars.next() == Some('[') {
+            for ch in chars.by_ref() {
+                if ch.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_format() {
+        let output = "Usage: mytool [OPTIONS] <FILE>\n\nOptions:\n  -v, --verbose  Print more\n      --color <WHEN>\tColorize\n  - not an option\n";
+        let manual = format("mytool", "mytool -h", output);
+
+        assert_eq!(
+            manual.headings(),
+            vec![
+                (0, String::from("mytool -h")),
+                (5, String::from("Options:")),
+            ]
+        );
+
+        let bold = |line: usize| -> Vec<String> {
+            manual.lines[line]
+                .iter()
+                .filter(|fragment| fragment.emphasis == Emphasis::Bold)
+                .map(|fragment| fragment.text.clone())
+                .collect()
+        };
+        assert_eq!(bold(6), vec!["-v, --verbose"]);
+        assert_eq!(bold(7), vec!["--color <WHEN>"]);
+        assert!(bold(8).is_empty());
+        assert_eq!(
+            manual.plain_lines()[3],
+            "       Usage: mytool [OPTIONS] <FILE>"
+        );
+    }
+
+    #[test]
+    fn test_strip_escapes() {
+        assert_eq!(strip_escapes("\x1b[1;32mUsage:\x1b[0m x"), "Usage: x");
+    }
+
+    #[test]
+    fn test_run() {
+        let timeout = Duration::from_millis(2000);
+        assert_eq!(
+            run("sh", &["-c", "echo usage >&2; exit 2"], "80", timeout).unwrap(),
+            "usage\n"
+        );
+        assert!(run("sh", &["-c", "true"], "80", timeout).is_err());
+        assert!(run("sh", &["-c", "sleep 5"], "80", Duration::from_millis(50)).is_err());
+
+        // The background process keeps the output open after `sh` exited.
+        let start = Instant::now();
+        let script = "echo usage; sleep 5 &";
+        assert!(run("sh", &["-c", script], "80", Duration::from_millis(200)).is_err());
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_is_builtin() {
+        let timeout = Duration::from_millis(2000);
+        assert!(is_builtin("cd", timeout));
+        assert!(!is_builtin("cd; echo builtin", timeout));
+        assert!(!is_builtin("$(echo cd)", timeout));
+    }
+}
@@ -0,0 +1,116 @@
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use super::manual::Anchor;
+use super::page::PageRef;
+use super::store;
+
+/// The recently read pages, most recent first, persisted in the data directory.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct History {
+    #[serde(default, rename = "entry")]
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Entry {
+    pub(crate) page: PageRef,
+    pub(crate) opened: DateTime<Local>,
+    /// Where reading stopped.
+    pub(crate) position: Option<Anchor>,
+}
+
+impl History {
+    const FILE: &str = "history.toml";
+
+    /// The maximum number of remembered pages.
+    const MAX_ENTRIES: usize = 200;
+
+    /// Loads the history, or starts with an empty one if the file is broken.
+    pub(crate) fn load_or_default() -> (Self, Option<anyhow::Error>) {
+        store::load_or_default(Self::FILE)
+    }
+
+    pub(crate) fn save(&self) -> Result<()> {
+        store::save(Self::FILE, self)
+    }
+
+    pub(crate) fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Records that a page was opened, moving it to the top of the history.
+    pub(crate) fn record(&mut self, page: &PageRef, opened: DateTime<Local>) {
+        let position = self.position(page).cloned();
+        self.entries.retain(|entry| entry.page != *page);
+        self.entries.insert(
+            0,
+            Entry {
+                page: page.clone(),
+                opened,
+                position,
+            },
+        );
+        self.entries.truncate(Self::MAX_ENTRIES);
+    }
+
+    /// Remembers where reading a page stopped.
+    pub(crate) fn set_position(&mut self, page: &PageRef, position: Anchor) {
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.page == *page) {
+            entry.position = Some(position);
+        }
+    }
+
+    pub(crate) fn position(&self, page: &PageRef) -> Option<&Anchor> {
+        let entry = self.entries.iter().find(|entry| entry.page == *page)?;
+        entry.position.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::{with_section, Manual};
+
+    #[test]
+    fn test_record() {
+        let mut history = History::default();
+        let (tar, jq) = (PageRef::new("tar", Some("1")), PageRef::new("jq", None));
+        let position = Anchor {
+            heading: String::from("OPTIONS"),
+            offset: 3,
+            text: String::new(),
+        };
+
+        history.record(&tar, Local::now());
+        history.set_position(&tar, position.clone());
+        history.record(&jq, Local::now());
+        history.record(&tar, Local::now());
+
+        let pages: Vec<_> = history.entries().iter().map(|e| &e.page).collect();
+        assert_eq!(pages, vec![&tar, &jq]);
+        assert_eq!(history.position(&tar), Some(&position));
+        assert_eq!(history.position(&jq), None);
+    }
+
+    #[test]
+    fn test_without_section() {
+        let manual = Manual::plain("TAR(1)    User Commands    TAR(1)\n\nNAME\n       tar");
+        let mut history = History::default();
+        let position = Anchor {
+            heading: String::from("NAME"),
+            offset: 1,
+            text: String::from("tar"),
+        };
+
+        let tar = with_section(&PageRef::new("tar", Some("1")), &manual);
+        history.record(&tar, Local::now());
+        history.set_position(&tar, position.clone());
+
+        let tar = with_section(&PageRef::new("tar", None), &manual);
+        assert_eq!(history.position(&tar), Some(&position));
+        history.record(&tar, Local::now());
+        assert_eq!(history.entries().len(), 1);
+    }
+}
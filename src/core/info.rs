@@ -0,0 +1,592 @@
+use anyhow::{anyhow, Context, Result};
+use flate2::read::GzDecoder;
+use std::{
+    collections::HashMap,
+    env, fs,
+    io::Read,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use super::manual::{Emphasis, Manual};
+
+/// The directories searched for info files after the ones in `$INFOPATH`.
+const INFO_DIRS: [&str; 3] = [
+    "/usr/share/info",
+    "/usr/local/share/info",
+    "/opt/homebrew/share/info",
+];
+
+/// Starts a node, and the tables at the end of an info file.
+const SEPARATOR: char = '\x1f';
+
+/// Whether there is an info manual for a program, by name. Finding out reads
+/// the `dir` files, thus it is done once per name.
+static EXISTS: Mutex<Option<HashMap<String, bool>>> = Mutex::new(None);
+
+/// A GNU info manual, e.g. `/usr/share/info/tar.info.gz`, split into its nodes.
+#[derive(Debug, Default)]
+pub(crate) struct InfoManual {
+    pub(crate) name: String,
+    nodes: Vec<Node>,
+    /// The node of the `dir` entry the manual was found by, e.g.
+    /// `ls invocation` for `ls`.
+    entry: Option<String>,
+}
+
+/// A node of an info manual with its neighbours.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Node {
+    pub(crate) name: String,
+    pub(crate) next: Option<String>,
+    pub(crate) prev: Option<String>,
+    pub(crate) up: Option<String>,
+    pub(crate) lines: Vec<String>,
+    /// Whether the node is (part of) the index.
+    is_index: bool,
+}
+
+/// The links in the header of a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Link {
+    Next,
+    Prev,
+    Up,
+}
+
+/// A menu entry or a cross-reference, e.g. `*Note Tar Options::`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Reference {
+    pub(crate) label: String,
+    /// The manual of the node, if it is another one, e.g. `coreutils`.
+    pub(crate) file: Option<String>,
+    pub(crate) node: String,
+    /// The line of the reference and its columns in characters. References
+    /// that span lines are cut at the end of their first line.
+    pub(crate) line: usize,
+    pub(crate) columns: Range<usize>,
+}
+
+/// An entry of the index of a manual.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct IndexEntry {
+    pub(crate) entry: String,
+    pub(crate) node: String,
+    /// The line within the node.
+    pub(crate) line: Option<usize>,
+}
+
+impl InfoManual {
+    /// Reads the info manual of a program, e.g. `tar`, or the manual that
+    /// its entry in the `dir` file points to, e.g. `coreutils` for `ls`.
+    pub(crate) fn load(name: &str) -> Result<Self> {
+        if let Some(path) = find(name) {
+            return Self::read(name, &path);
+        }
+
+        let (file, node) = find_entry(name).ok_or_else(|| anyhow!("no info manual for {name}"))?;
+        let path = find(&file).ok_or_else(|| anyhow!("no info manual {file} for {name}"))?;
+        let mut manual = Self::read(&file, &path)?;
+        manual.entry = Some(node);
+        Ok(manual)
+    }
+
+    /// Reads an info file. Manuals that are split into several files, e.g.
+    /// `tar.info-1.gz`, are read completely.
+    fn read(name: &str, path: &Path) -> Result<Self> {
+        let text = read(path)?;
+
+        let mut manual = Self::parse(name, &text);
+        for file in indirect_files(&text) {
+            let mut path = path.with_file_name(&file);
+            if !path.exists() {
+                path = path.with_file_name(format!("{file}.gz"));
+            }
+            manual.nodes.extend(Self::parse(name, &read(&path)?).nodes);
+        }
+        Ok(manual)
+    }
+
+    /// Whether there is an info manual for a program.
+    pub(crate) fn exists(name: &str) -> bool {
+        if let Some(&exists) = EXISTS
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|names| names.get(name))
+        {
+            return exists;
+        }
+
+        let exists = find(name).is_some() || find_entry(name).is_some();
+        EXISTS
+            .lock()
+            .unwrap()
+            .get_or_insert_with(HashMap::new)
+            .insert(name.to_string(), exists);
+        exists
+    }
+
+    fn parse(name: &str, text: &str) -> Self {
+        let nodes = text
+            .split(SEPARATOR)
+            .skip(1)
+            .filter_map(Node::parse)
+            .collect();
+        Self {
+            name: name.to_string(),
+            nodes,
+            entry: None,
+        }
+    }
+
+    /// Returns a node by name, ignoring case if there is no exact match.
+    pub(crate) fn node(&self, name: &str) -> Option<&Node> {
+        let name = name.trim();
+        self.nodes
+            .iter()
+            .find(|node| node.name == name)
+            .or_else(|| {
+                let name = name.to_lowercase();
+                self.nodes
+                    .iter()
+                    .find(|node| node.name.to_lowercase() == name)
+            })
+    }
+
+    /// Returns the `Top` node, or the first node if there is none.
+    pub(crate) fn top(&self) -> Option<&Node> {
+        self.node("Top").or_else(|| self.nodes.first())
+    }
+
+    /// Returns the node to start reading at: the node of the `dir` entry
+    /// the manual was found by, or the `Top` node.
+    pub(crate) fn start(&self) -> Option<&Node> {
+        self.entry
+            .as_deref()
+            .and_then(|entry| self.node(entry))
+            .or_else(|| self.top())
+    }
+
+    /// Returns the entries of all index nodes.
+    pub(crate) fn index(&self) -> Vec<IndexEntry> {
+        self.nodes
+            .iter()
+            .filter(|node| node.is_index)
+            .flat_map(|node| {
+                node.references().into_iter().map(|reference| {
+                    let rest = node.lines[reference.line]
+                        .chars()
+                        .skip(reference.columns.end)
+                        .collect::<String>();
+                    IndexEntry {
+                        entry: reference.label,
+                        node: reference.node,
+                        line: index_line(&rest),
+                    }
+                })
+            })
+            .collect()
+    }
+}
+
+impl Node {
+    /// Parses a node from the text after a separator, which starts with a
+    /// header like `File: tar.info,  Node: Top,  Next: Introduction,  Up: (dir)`.
+    fn parse(text: &str) -> Option<Self> {
+        let text = text.trim_start_matches(['\n', '\r']);
+        let (header, body) = text.split_once('\n').unwrap_or((text, ""));
+        if !header.starts_with("File:") {
+            return None;
+        }
+
+        let mut node = Node::default();
+        for field in header.split(",  ") {
+            let Some((key, value)) = field.trim().split_once(':') else {
+                continue;
+            };
+            let value = value.trim().to_string();
+            match key {
+                "Node" => node.name = value,
+                "Next" => node.next = Some(value),
+                "Prev" => node.prev = Some(value),
+                "Up" => node.up = Some(value),
+                _ => {}
+            }
+        }
+
+        let (body, is_index) = strip_markers(body);
+        node.is_index = is_index || node.name.to_lowercase().contains("index");
+        node.lines = body.lines().map(str::to_string).collect();
+        Some(node)
+    }
+
+    /// Returns the manual, if it is another one, and the node of a link.
+    pub(crate) fn link(&self, link: Link) -> Option<(Option<String>, String)> {
+        let target = match link {
+            Link::Next => &self.next,
+            Link::Prev => &self.prev,
+            Link::Up => &self.up,
+        };
+        target.as_deref().map(split_target)
+    }
+
+    /// Returns the menu entries and cross-references of the node in order.
+    pub(crate) fn references(&self) -> Vec<Reference> {
+        let text = self.lines.join("\n");
+        let lower = text.to_ascii_lowercase();
+        let mut references = Vec::new();
+
+        // Menu entries start a line with `* ` after the `* Menu:` line.
+        let mut offset = 0;
+        let mut in_menu = false;
+        for line in &self.lines {
+            if line.starts_with("* Menu:") {
+                in_menu = true;
+            } else if let Some(entry) = line.strip_prefix("* ").filter(|_| in_menu) {
+                if let Some((label, target, len)) = parse_reference(entry) {
+                    let start = offset + 2;
+                    references.push((start, start + len, label, target));
+                }
+            }
+            offset += line.len() + 1;
+        }
+
+        // Cross-references start with `*Note` or `*note`.
+        let mut position = 0;
+        while let Some(found) = lower[position..].find("*note") {
+            let start = position + found + "*note".len();
+            position = start;
+            if !text[start..].starts_with(char::is_whitespace) {
+                continue;
+            }
+            let label_start = start + (text[start..].len() - text[start..].trim_start().len());
+            if let Some((label, target, len)) = parse_reference(&text[label_start..]) {
+                references.push((label_start, label_start + len, label, target));
+            }
+        }
+
+        references.sort_by_key(|(start, ..)| *start);
+        references
+            .into_iter()
+            .map(|(start, end, label, target)| {
+                let line = text[..start].matches('\n').count();
+                let line_start = text[..start].rfind('\n').map_or(0, |index| index + 1);
+                let line_end = text[start..].find('\n').map_or(text.len(), |i| start + i);
+                let column = text[line_start..start].chars().count();
+                let len = text[start..end.min(line_end)].chars().count();
+                let (file, node) = split_target(&target);
+                Reference {
+                    label,
+                    file,
+                    node,
+                    line,
+                    columns: column..column + len,
+                }
+            })
+            .collect()
+    }
+
+    /// Converts the node to a manual with the references underlined.
+    pub(crate) fn to_manual(&self, references: &[Reference]) -> Manual {
+        let mut manual = Manual::default();
+        for (index, line) in self.lines.iter().enumerate() {
+            manual.lines.push(Vec::new());
+            let chars: Vec<char> = line.chars().collect();
+            let mut column = 0;
+            for reference in references.iter().filter(|r| r.line == index) {
+                let start = reference.columns.start.clamp(column, chars.len());
+                let end = reference.columns.end.clamp(start, chars.len());
+                push(&mut manual, &chars[column..start], Emphasis::None);
+                push(&mut manual, &chars[start..end], Emphasis::Underline);
+                column = end;
+            }
+            push(&mut manual, &chars[column..], Emphasis::None);
+        }
+        manual
+    }
+}
+
+fn push(manual: &mut Manual, chars: &[char], emphasis: Emphasis) {
+    if !chars.is_empty() {
+        manual.push(&chars.iter().collect::<String>(), emphasis);
+    }
+}
+
+/// Returns the info file of a program in `$INFOPATH` or the usual directories.
+fn find(name: &str) -> Option<PathBuf> {
+    files(name).next()
+}
+
+/// Returns the info files of a name in all directories, in the order of
+/// `$INFOPATH` and the usual directories.
+fn files(name: &str) -> impl Iterator<Item = PathBuf> {
+    let infopath = env::var("INFOPATH").unwrap_or_default();
+    let dirs: Vec<_> = infopath
+        .split(':')
+        .filter(|dir| !dir.is_empty())
+        .chain(INFO_DIRS)
+        .map(PathBuf::from)
+        .collect();
+
+    let name = name.to_lowercase();
+    let files = [
+        format!("{name}.info.gz"),
+        format!("{name}.info"),
+        format!("{name}.gz"),
+        name,
+    ];
+    dirs.into_iter()
+        .flat_map(move |dir| files.clone().map(|file| dir.join(file)))
+        .filter(|path| path.is_file())
+}
+
+/// Looks a program up in the `dir` files, which list the manuals and the
+/// programs documented in them. Returns the manual and the node.
+fn find_entry(name: &str) -> Option<(String, String)> {
+    files("dir")
+        .filter_map(|path| read(&path).ok())
+        .find_map(|text| dir_entry(&text, name))
+}
+
+/// Returns the manual and the node of an entry of a `dir` file, e.g.
+/// `* ls: (coreutils)ls invocation.` for `ls`.
+fn dir_entry(text: &str, name: &str) -> Option<(String, String)> {
+    text.split(SEPARATOR)
+        .filter_map(Node::parse)
+        .flat_map(|node| node.references())
+        .find_map(|reference| match reference.file {
+            Some(file) if reference.label.eq_ignore_ascii_case(name) => {
+                Some((file, reference.node))
+            }
+            _ => None,
+        })
+}
+
+/// Reads an info file, decompressing it if it ends with `.gz`.
+pub(super) fn read(path: &Path) -> Result<String> {
+    let bytes = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    if path.extension().is_some_and(|extension| extension == "gz") {
+        let mut text = String::new();
+        GzDecoder::new(bytes.as_slice())
+            .read_to_string(&mut text)
+            .with_context(|| format!("failed to decompress {}", path.display()))?;
+        Ok(text)
+    } else {
+        Ok(String::from_utf8_lossy(&bytes).to_string())
+    }
+}
+
+/// Returns the files listed in the indirect table of a split manual,
+/// i.e. the lines like `tar.info-1: 1234` after `Indirect:`.
+fn indirect_files(text: &str) -> Vec<String> {
+    text.split(SEPARATOR)
+        .map(|table| table.trim_start_matches(['\n', '\r']))
+        .find_map(|table| table.strip_prefix("Indirect:"))
+        .map(|table| {
+            table
+                .lines()
+                .filter_map(|line| line.split_once(':'))
+                .map(|(file, _)| file.trim().to_string())
+                .filter(|file| !file.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Removes the markers of index nodes and images, e.g. `\0\x08[index\0\x08]`.
+/// Returns whether the text was marked as an index.
+fn strip_markers(text: &str) -> (String, bool) {
+    let mut text = text.to_string();
+    let mut is_index = false;
+    while let Some(start) = text.find("\0\x08[") {
+        let Some(end) = text[start..].find("\0\x08]").map(|end| start + end + 3) else {
+            break;
+        };
+        is_index |= text[start + 3..end].starts_with("index");
+        text.replace_range(start..end, "");
+    }
+    (text, is_index)
+}
+
+/// Parses a reference after `* ` or `*Note `, either `Node::` or `Label: Target.`
+/// Returns the label, the target and the length of the reference in bytes.
+fn parse_reference(text: &str) -> Option<(String, String, usize)> {
+    let colon = text.find(':')?;
+    let label = &text[..colon];
+    if label.is_empty() || label.contains("\n\n") || label.len() > 200 {
+        return None;
+    }
+    if text[colon + 1..].starts_with(':') {
+        return Some((collapse(label), collapse(label), colon + 2));
+    }
+
+    let rest = &text[colon + 1..];
+    let start = rest.len() - rest.trim_start().len();
+    let target = &rest[start..];
+    let end = target
+        .char_indices()
+        .find(|(index, ch)| match ch {
+            ',' | '\t' | '\n' => true,
+            '.' => target[index + 1..]
+                .chars()
+                .next()
+                .is_none_or(char::is_whitespace),
+            _ => false,
+        })
+        .map_or(target.len(), |(index, _)| index);
+    if end == 0 {
+        return None;
+    }
+
+    let len = colon + 1 + start + end;
+    Some((collapse(label), collapse(&target[..end]), len))
+}
+
+/// Splits a target like `(coreutils)ls invocation` into the manual and the node.
+fn split_target(target: &str) -> (Option<String>, String) {
+    if let Some((file, node)) = target
+        .strip_prefix('(')
+        .and_then(|target| target.split_once(')'))
+    {
+        let node = if node.trim().is_empty() { "Top" } else { node };
+        return (Some(file.to_string()), node.trim().to_string());
+    }
+    (None, target.to_string())
+}
+
+/// Returns the line of an index entry from the text after it, e.g. `(line 12)`.
+fn index_line(text: &str) -> Option<usize> {
+    let (_, line) = text.split_once("(line")?;
+    line.trim().trim_end_matches(')').trim().parse().ok()
+}
+
+/// Collapses whitespace, including line breaks, to single spaces.
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const MANUAL: &str = "This is tar.info.\n\
+        \x1f\nFile: tar.info,  Node: Top,  Next: Introduction,  Up: (dir)\n\
+        \n\
+        GNU tar\n\
+        *******\n\
+        \n\
+        See *note Tar\n\
+        Options::, or *Note the manual: (coreutils)ls invocation.\n\
+        \n\
+        * Menu:\n\
+        \n\
+        * Introduction::      What tar does.\n\
+        * Index: Concept Index.  All concepts.\n\
+        \x1f\nFile: tar.info,  Node: Introduction,  Prev: Top,  Up: Top\n\
+        \n\
+        1 Introduction\n\
+        \x1f\nFile: tar.info,  Node: Concept Index,  Prev: Introduction,  Up: Top\n\
+        \n\
+        \0\x08[index\0\x08]\n\
+        * Menu:\n\
+        \n\
+        * archive:                        Introduction.         (line 6)\n\
+        \x1f\nTag Table:\nNode: Top\x7f18\n\x1f\nEnd Tag Table\n";
+
+    #[test]
+    fn test_parse() {
+        let manual = InfoManual::parse("tar", MANUAL);
+
+        let top = manual.top().unwrap();
+        assert_eq!(top.name, "Top");
+        assert_eq!(top.next.as_deref(), Some("Introduction"));
+        assert_eq!(top.up.as_deref(), Some("(dir)"));
+        assert_eq!(
+            top.link(Link::Up),
+            Some((Some(String::from("dir")), String::from("Top")))
+        );
+        assert_eq!(top.lines[1], "GNU tar");
+
+        let introduction = manual.node("introduction").unwrap();
+        assert_eq!(introduction.prev.as_deref(), Some("Top"));
+        assert_eq!(manual.nodes.len(), 3);
+    }
+
+    #[test]
+    fn test_references() {
+        let manual = InfoManual::parse("tar", MANUAL);
+        let references = manual.top().unwrap().references();
+
+        let targets: Vec<_> = references
+            .iter()
+            .map(|r| (r.label.as_str(), r.file.as_deref(), r.node.as_str(), r.line))
+            .collect();
+        assert_eq!(
+            targets,
+            vec![
+                ("Tar Options", None, "Tar Options", 4),
+                ("the manual", Some("coreutils"), "ls invocation", 5),
+                ("Introduction", None, "Introduction", 9),
+                ("Index", None, "Concept Index", 10),
+            ]
+        );
+
+        // `*note Tar` is cut at the end of its line.
+        assert_eq!(references[0].columns, 10..13);
+        assert_eq!(references[2].columns, 2..16);
+
+        let manual = manual.top().unwrap().to_manual(&references);
+        let underlined: Vec<_> = manual.lines[9]
+            .iter()
+            .filter(|fragment| fragment.emphasis == Emphasis::Underline)
+            .map(|fragment| fragment.text.as_str())
+            .collect();
+        assert_eq!(underlined, vec!["Introduction::"]);
+    }
+
+    #[test]
+    fn test_index() {
+        let manual = InfoManual::parse("tar", MANUAL);
+        assert_eq!(
+            manual.index(),
+            vec![IndexEntry {
+                entry: String::from("archive"),
+                node: String::from("Introduction"),
+                line: Some(6),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_dir_entry() {
+        let dir = "This is the file .../info/dir.\n\
+            \x1f\nFile: dir,\tNode: Top\tThis is the top of the INFO tree\n\
+            \n\
+            * Menu:\n\
+            \n\
+            Basics\n\
+            * Common options: (coreutils)Common options.\n\
+            * ls: (coreutils)ls invocation.                 List directory contents.\n\
+            * tar: (tar).                                   Making tape (or disk) archives.\n";
+
+        assert_eq!(
+            dir_entry(dir, "ls"),
+            Some((String::from("coreutils"), String::from("ls invocation")))
+        );
+        assert_eq!(
+            dir_entry(dir, "TAR"),
+            Some((String::from("tar"), String::from("Top")))
+        );
+        assert_eq!(dir_entry(dir, "cp"), None);
+    }
+
+    #[test]
+    fn test_indirect_files() {
+        let text =
+            "preamble\x1f\nIndirect:\ntar.info-1: 1234\ntar.info-2: 56789\n\x1f\nTag Table:\n";
+        assert_eq!(indirect_files(text), vec!["tar.info-1", "tar.info-2"]);
+        assert!(indirect_files(MANUAL).is_empty());
+    }
+}
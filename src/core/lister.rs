@@ -1,46 +1,205 @@
-use anyhow::{anyhow, Result};
-use std::{collections::HashSet, process::Command};
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+use std::{
+    collections::{HashMap, HashSet},
+    env, fs,
+    path::PathBuf,
+    process::Command,
+};
+
+use super::page::PageRef;
+
+/// The man sections and their titles.
+pub(crate) const SECTIONS: [(&str, &str); 9] = [
+    ("1", "User commands"),
+    ("2", "System calls"),
+    ("3", "Library calls"),
+    ("4", "Special files"),
+    ("5", "File formats"),
+    ("6", "Games"),
+    ("7", "Miscellaneous"),
+    ("8", "System management"),
+    ("9", "Kernel routines"),
+];
+
+/// The exit status of man-db's `apropos` if nothing was found.
+const NOTHING_FOUND: i32 = 16;
+
+/// A page in the index of `man -k`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct Entry {
+    pub(crate) name: String,
+    pub(crate) section: String,
+    pub(crate) description: String,
+    /// The source file of the page, if it was found in the man path.
+    pub(crate) path: Option<PathBuf>,
+}
 
 pub(super) struct Lister;
 
 impl Lister {
+    /// Returns the pages of a section as `name (section)`.
     pub(super) fn list_section(section: String) -> Result<Vec<String>> {
-        let output = Command::new("man")
-            .arg("-k")
-            .arg("-S")
-            .arg(section)
-            .arg(".")
+        let commands: HashSet<String> = Self::apropos(".", Some(&section))?
+            .into_iter()
+            .map(|entry| format!("{} ({})", entry.name, entry.section))
+            .collect();
+
+        let mut commands: Vec<_> = commands.into_iter().collect();
+        commands.sort();
+
+        Ok(commands)
+    }
+
+    /// Returns the pages whose name or description matches a regular
+    /// expression, with their source files, sorted by name and section.
+    pub(super) fn entries(query: &str, section: Option<&str>) -> Result<Vec<Entry>> {
+        let mut entries = Self::apropos(query, section)?;
+        entries.sort_by(|a, b| (&a.name, &a.section).cmp(&(&b.name, &b.section)));
+        entries.dedup_by(|a, b| a.name == b.name && a.section == b.section);
+
+        let paths = source_paths();
+        for entry in &mut entries {
+            let key = (entry.name.clone(), entry.section.clone());
+            entry.path = paths.get(&key).cloned();
+        }
+
+        Ok(entries)
+    }
+
+    /// Runs `man -k`. Finding nothing is not an error.
+    fn apropos(query: &str, section: Option<&str>) -> Result<Vec<Entry>> {
+        let mut command = Command::new("man");
+        command.arg("-k");
+        if let Some(section) = section {
+            command.arg("-S").arg(section);
+        }
+        let output = command
+            .arg(query)
             .output()
-            .expect("Failed to execute `man -k .`");
+            .context("failed to execute `man -k`")?;
 
+        let stdout = String::from_utf8_lossy(&output.stdout);
         if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if output.status.code() == Some(NOTHING_FOUND) || stderr.contains("nothing appropriate")
+            {
+                return Ok(Vec::new());
+            }
             return Err(anyhow!(
                 "command exited with a non-zero status: {}",
                 output.status
             ));
         }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let commands: HashSet<String> = stdout
-            .lines()
-            .filter_map(|line| line.split(" - ").next())
-            .filter_map(|line| line.split(", ").next())
-            .filter(|cmd| {
-                !cmd.starts_with(|c: char| {
-                    matches!(
-                        c,
-                        ' ' | '!' | '$' | '*' | '%' | ':' | '<' | '-' | '/' | '.' | '@' | '['
-                    )
-                }) && !cmd.starts_with("Yet another")
-                    && !cmd.starts_with("Other_name")
-            })
-            .map(str::trim)
-            .map(String::from)
-            .collect();
+        Ok(stdout.lines().filter_map(parse_entry).collect())
+    }
+}
 
-        let mut commands: Vec<_> = commands.into_iter().collect();
-        commands.sort();
+/// Parses a line of `man -k`, e.g. `tar (1) - an archiving utility`.
+/// Only the first of several names is used, e.g. `gzip(1), gunzip(1) - ...`.
+fn parse_entry(line: &str) -> Option<Entry> {
+    let (names, description) = line.split_once(" - ")?;
+    let name = names.split(", ").next()?.trim();
 
-        Ok(commands)
+    let excluded = name.starts_with(|c: char| {
+        matches!(
+            c,
+            ' ' | '!' | '$' | '*' | '%' | ':' | '<' | '-' | '/' | '.' | '@' | '['
+        )
+    }) || name.starts_with("Yet another")
+        || name.starts_with("Other_name");
+    if excluded {
+        return None;
+    }
+
+    let page: PageRef = name.parse().ok()?;
+    Some(Entry {
+        name: page.name,
+        section: page.section.unwrap_or_default(),
+        description: description.trim().to_string(),
+        path: None,
+    })
+}
+
+/// Finds the source files of the pages in the man path, e.g.
+/// `/usr/share/man/man1/tar.1.gz`. Earlier directories take precedence.
+fn source_paths() -> HashMap<(String, String), PathBuf> {
+    let manpath = Command::new("manpath")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .or_else(|| env::var("MANPATH").ok())
+        .unwrap_or_default();
+
+    let mut paths = HashMap::new();
+    for dir in manpath.split(':').filter(|dir| !dir.is_empty()) {
+        let Ok(sections) = fs::read_dir(dir) else {
+            continue;
+        };
+        let sections = sections
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with("man"));
+
+        for section in sections {
+            let Ok(files) = fs::read_dir(section.path()) else {
+                continue;
+            };
+            for file in files.filter_map(Result::ok) {
+                let file_name = file.file_name().to_string_lossy().to_string();
+                if let Some(key) = page_of_file(&file_name) {
+                    paths.entry(key).or_insert_with(|| file.path());
+                }
+            }
+        }
+    }
+    paths
+}
+
+/// Returns the name and section of a man page file, e.g. `tar.1.gz`.
+fn page_of_file(file_name: &str) -> Option<(String, String)> {
+    let stem = [".gz", ".bz2", ".xz", ".lzma", ".zst", ".Z"]
+        .iter()
+        .find_map(|ext| file_name.strip_suffix(ext))
+        .unwrap_or(file_name);
+
+    let (name, section) = stem.rsplit_once('.')?;
+    if name.is_empty() || !section.starts_with(|c: char| c.is_ascii_digit() || c == 'n') {
+        return None;
+    }
+    Some((name.to_string(), section.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_entry() {
+        let entry = parse_entry("tar (1)              - an archiving utility").unwrap();
+        assert_eq!(entry.name, "tar");
+        assert_eq!(entry.section, "1");
+        assert_eq!(entry.description, "an archiving utility");
+
+        let entry =
+            parse_entry("gzip(1), gunzip(1), zcat(1) - compression/decompression tool").unwrap();
+        assert_eq!((entry.name.as_str(), entry.section.as_str()), ("gzip", "1"));
+
+        assert_eq!(parse_entry("--help (1) - nonsense"), None);
+        assert_eq!(parse_entry("no description"), None);
+    }
+
+    #[test]
+    fn test_page_of_file() {
+        assert_eq!(
+            page_of_file("ssh_config.5.gz"),
+            Some((String::from("ssh_config"), String::from("5")))
+        );
+        assert_eq!(
+            page_of_file("SSL_read.3ssl"),
+            Some((String::from("SSL_read"), String::from("3ssl")))
+        );
+        assert_eq!(page_of_file("README"), None);
     }
 }
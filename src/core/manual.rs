@@ -0,0 +1,682 @@
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// The emphasis of a piece of man page text.
+///
+/// The core only records the emphasis, the UI maps it to a theme style.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Emphasis {
+    #[default]
+    None,
+    Bold,
+    Italic,
+    Underline,
+    BoldUnderline,
+    BoldItalic,
+    ItalicUnderline,
+    BoldItalicUnderline,
+}
+
+impl Emphasis {
+    fn from_flags(bold: bool, italic: bool, underline: bool) -> Self {
+        match (bold, italic, underline) {
+            (true, true, true) => Emphasis::BoldItalicUnderline,
+            (true, true, false) => Emphasis::BoldItalic,
+            (true, false, true) => Emphasis::BoldUnderline,
+            (true, false, false) => Emphasis::Bold,
+            (false, true, true) => Emphasis::ItalicUnderline,
+            (false, true, false) => Emphasis::Italic,
+            (false, false, true) => Emphasis::Underline,
+            (false, false, false) => Emphasis::None,
+        }
+    }
+}
+
+/// A run of text with the same emphasis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Fragment {
+    pub(crate) text: String,
+    pub(crate) emphasis: Emphasis,
+}
+
+/// A line in a manual given as a heading and the number of lines below it,
+/// so that it roughly survives rendering the manual with a different width.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Anchor {
+    /// The heading of the section, empty for lines before the first heading.
+    pub(crate) heading: String,
+    pub(crate) offset: usize,
+    /// The text of the line with collapsed whitespace.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub(crate) text: String,
+}
+
+/// An example or another block of code in a manual.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CodeBlock {
+    /// The heading of the section of the block.
+    pub(crate) heading: String,
+    pub(crate) lines: Range<usize>,
+    /// The text without its common indentation.
+    pub(crate) text: String,
+}
+
+/// A rendered man page.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct Manual {
+    pub(crate) lines: Vec<Vec<Fragment>>,
+}
+
+impl Manual {
+    /// Creates an unformatted manual, e.g. to show an error message.
+    pub(crate) fn plain(text: &str) -> Self {
+        let mut manual = Self::default();
+        for line in text.lines() {
+            manual.lines.push(Vec::new());
+            if !line.is_empty() {
+                manual.push(line, Emphasis::None);
+            }
+        }
+        manual
+    }
+
+    /// Parses the output of `man`.
+    ///
+    /// Formatting is given as overstrike sequences:
+    /// - Bold: `c\u{8}c`
+    /// - Underline: `_\u{8}c`
+    /// - Bold and underline: `_\u{8}c\u{8}c`
+    ///
+    /// SGR escape sequences for bold, italic and underline are understood as well.
+    pub(crate) fn parse(input: &str) -> Self {
+        let mut manual = Self::default();
+        let (mut bold, mut italic, mut underline) = (false, false, false);
+
+        for line in input.lines() {
+            manual.lines.push(Vec::new());
+
+            let chars: Vec<char> = line.chars().collect();
+
+            let mut i = 0;
+            while i < chars.len() {
+                // SGR escape sequence, e.g. `\x1b[1m`.
+                if chars[i] == '\x1b' && chars.get(i + 1) == Some(&'[') {
+                    let end = chars[i..].iter().position(|ch| ch.is_ascii_alphabetic());
+                    // An unterminated escape, e.g. cut off by `head -c`, is
+                    // kept as text without the escape character.
+                    let Some(end) = end.map(|end| i + end) else {
+                        i += 1;
+                        continue;
+                    };
+                    if chars[end] == 'm' {
+                        let params: String = chars[i + 2..end].iter().collect();
+                        apply_sgr(&params, &mut bold, &mut italic, &mut underline);
+                    }
+                    i = end + 1;
+                    continue;
+                }
+
+                // Overstrike sequence, e.g. `c\u{8}c`.
+                let mut strikes = vec![chars[i]];
+                while chars.get(i + 1) == Some(&'\u{8}') && i + 2 < chars.len() {
+                    strikes.push(chars[i + 2]);
+                    i += 2;
+                }
+                i += 1;
+
+                let ch = strikes
+                    .iter()
+                    .rev()
+                    .find(|ch| **ch != '_')
+                    .copied()
+                    .unwrap_or('_');
+                let struck_bold = strikes.iter().filter(|c| **c == ch).count() > 1;
+                let struck_underline = ch != '_' && strikes.contains(&'_');
+
+                let emphasis = Emphasis::from_flags(
+                    bold || struck_bold,
+                    italic,
+                    underline || struck_underline,
+                );
+                let ch = if ch == '`' { '\'' } else { ch };
+                manual.push(&ch.to_string(), emphasis);
+            }
+        }
+
+        manual
+    }
+
+    /// Appends text to the last line, merging it with the last fragment
+    /// if the emphasis is the same.
+    pub(crate) fn push(&mut self, text: &str, emphasis: Emphasis) {
+        if self.lines.is_empty() {
+            self.lines.push(Vec::new());
+        }
+        let line = self.lines.last_mut().unwrap();
+        match line.last_mut() {
+            Some(last) if last.emphasis == emphasis => last.text.push_str(text),
+            _ => line.push(Fragment {
+                text: text.to_string(),
+                emphasis,
+            }),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.lines.iter().all(Vec::is_empty)
+    }
+
+    /// Returns the line numbers and titles of the section headings, e.g. `NAME`.
+    ///
+    /// Headings are the lines that are not indented, except for the header
+    /// and footer which end with the page name, e.g. `TAR(1)`.
+    pub(crate) fn headings(&self) -> Vec<(usize, String)> {
+        self.plain_lines()
+            .into_iter()
+            .enumerate()
+            .filter(|(_, line)| line.starts_with(|ch: char| !ch.is_whitespace()))
+            .filter(|(_, line)| !line.trim_end().ends_with(')'))
+            .map(|(index, line)| (index, line.trim_end().to_string()))
+            .collect()
+    }
+
+    /// Returns the sections with the given headings, compared case-insensitively,
+    /// in the order of the manual. The footer is left out.
+    pub(crate) fn sections(&self, headings: &[String]) -> Manual {
+        let plain = self.plain_lines();
+        let all = self.headings();
+
+        let mut manual = Manual::default();
+        for (index, (start, heading)) in all.iter().enumerate() {
+            if !headings
+                .iter()
+                .any(|h| h.trim().eq_ignore_ascii_case(heading))
+            {
+                continue;
+            }
+            let end = all
+                .get(index + 1)
+                .map_or(self.lines.len(), |(next, _)| *next);
+            let lines = (*start..end).filter(|line| !is_footer(&plain[*line]));
+            manual
+                .lines
+                .extend(lines.map(|line| self.lines[line].clone()));
+        }
+        manual
+    }
+
+    /// Returns the text of the section that contains a line: its heading and
+    /// the lines below it without their common indentation.
+    pub(crate) fn section_text(&self, line: usize) -> Option<String> {
+        let plain = self.plain_lines();
+        let headings = self.headings();
+        let index = headings.iter().rposition(|(start, _)| *start <= line)?;
+        let (start, heading) = &headings[index];
+        let end = headings
+            .get(index + 1)
+            .map_or(plain.len(), |(next, _)| *next);
+
+        let body: Vec<&str> = plain[start + 1..end]
+            .iter()
+            .filter(|line| !is_footer(line))
+            .map(String::as_str)
+            .collect();
+        Some(format!("{heading}\n{}", dedent(&body)))
+    }
+
+    /// Returns the examples: runs of lines in sections like `EXAMPLES` that
+    /// are indented deeper than the text before them. Indented text in other
+    /// sections is mostly the description of an option, thus it is left out.
+    pub(crate) fn code_blocks(&self) -> Vec<CodeBlock> {
+        let plain = self.plain_lines();
+        let mut blocks = Vec::new();
+        let mut heading = String::new();
+        // The indentation of the text that blocks are indented against.
+        let mut text_indent = None;
+        // The first line and the indentation of the current block.
+        let mut block: Option<(usize, usize)> = None;
+
+        for (index, line) in plain.iter().chain([&String::new()]).enumerate() {
+            let text = line.trim();
+            let indent = line.len() - line.trim_start().len();
+            if block.is_some_and(|(_, block_indent)| !text.is_empty() && indent >= block_indent) {
+                continue;
+            }
+
+            if let Some((start, _)) = block.take() {
+                let lines: Vec<&str> = plain[start..index].iter().map(String::as_str).collect();
+                blocks.push(CodeBlock {
+                    heading: heading.clone(),
+                    lines: start..index,
+                    text: dedent(&lines),
+                });
+            }
+
+            let is_example = heading.to_uppercase().contains("EXAMPLE");
+            match text_indent {
+                _ if text.is_empty() || is_footer(line) => {}
+                _ if indent == 0 => {
+                    heading = text.to_string();
+                    text_indent = None;
+                }
+                Some(text_indent) if is_example && indent > text_indent => {
+                    block = Some((index, indent))
+                }
+                _ => text_indent = Some(indent),
+            }
+        }
+        blocks
+    }
+
+    /// Returns the anchor of a line relative to the heading above it.
+    pub(crate) fn anchor(&self, line: usize) -> Anchor {
+        let text = self
+            .lines
+            .get(line)
+            .map(|fragments| {
+                let text: String = fragments.iter().map(|f| f.text.as_str()).collect();
+                normalize(&text)
+            })
+            .unwrap_or_default();
+
+        match self
+            .headings()
+            .into_iter()
+            .rev()
+            .find(|(index, _)| *index <= line)
+        {
+            Some((index, heading)) => Anchor {
+                heading,
+                offset: line - index,
+                text,
+            },
+            None => Anchor {
+                heading: String::new(),
+                offset: line,
+                text,
+            },
+        }
+    }
+
+    /// Returns the line of an anchor.
+    ///
+    /// The line is searched by its text within the section of the heading,
+    /// then within the whole manual, since the text may have moved. If the
+    /// text is not found, the offset below the heading is used. Returns `None`
+    /// if neither the text nor the heading exist.
+    pub(crate) fn resolve(&self, anchor: &Anchor) -> Option<usize> {
+        let headings = self.headings();
+        let lines = self.plain_lines();
+
+        let start = if anchor.heading.is_empty() {
+            Some(0)
+        } else {
+            headings
+                .iter()
+                .find(|(_, heading)| *heading == anchor.heading)
+                .map(|(index, _)| *index)
+        };
+        let end = start.and_then(|start| {
+            let next = headings.iter().find(|(index, _)| *index > start);
+            next.map(|(index, _)| *index)
+        });
+        let end = end.unwrap_or(lines.len());
+
+        if !anchor.text.is_empty() {
+            let found = start
+                .and_then(|start| find_text(&lines, start..end, &anchor.text))
+                .or_else(|| find_text(&lines, 0..lines.len(), &anchor.text));
+            if found.is_some() {
+                return found;
+            }
+        }
+
+        let last = end.saturating_sub(1);
+        start.map(|start| (start + anchor.offset).min(last))
+    }
+
+    /// Returns the version and date in the footer of a page, e.g.
+    /// `GNU tar 1.35    2023-07-18    TAR(1)`.
+    pub(crate) fn footer(&self) -> Option<Footer> {
+        let lines = self.plain_lines();
+        let footer = lines.iter().rev().find(|line| !line.trim().is_empty())?;
+        if footer.starts_with(char::is_whitespace) {
+            return None;
+        }
+
+        let parts: Vec<&str> = footer
+            .split("  ")
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .collect();
+        match parts[..] {
+            [version, date, _] => Some(Footer {
+                version: version.to_string(),
+                date: date.to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Formats the manual like the output of `man`, with overstrike sequences
+    /// that pagers like `less` understand. Italic text is underlined.
+    pub(crate) fn overstrike(&self) -> String {
+        let mut output = String::new();
+        for line in &self.lines {
+            for fragment in line {
+                for ch in fragment.text.chars() {
+                    match fragment.emphasis {
+                        _ if ch.is_whitespace() => {}
+                        Emphasis::None => {}
+                        Emphasis::Bold | Emphasis::BoldItalic => output.extend([ch, '\u{8}']),
+                        Emphasis::Italic | Emphasis::Underline | Emphasis::ItalicUnderline => {
+                            output.push_str("_\u{8}");
+                        }
+                        Emphasis::BoldUnderline | Emphasis::BoldItalicUnderline => {
+                            output.extend(['_', '\u{8}', ch, '\u{8}']);
+                        }
+                    }
+                    output.push(ch);
+                }
+            }
+            output.push('\n');
+        }
+        output
+    }
+
+    /// Returns the unformatted text of each line.
+    pub(crate) fn plain_lines(&self) -> Vec<String> {
+        self.lines
+            .iter()
+            .map(|line| line.iter().map(|fragment| fragment.text.as_str()).collect())
+            .collect()
+    }
+}
+
+/// The version and date in the footer of a page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Footer {
+    pub(crate) version: String,
+    pub(crate) date: String,
+}
+
+/// Whether a line is the header or the footer of a page, which end with the
+/// page name, e.g. `TAR(1)`.
+fn is_footer(line: &str) -> bool {
+    line.starts_with(|ch: char| !ch.is_whitespace()) && line.trim_end().ends_with(')')
+}
+
+/// Joins lines without their common indentation and the empty lines at the end.
+fn dedent(lines: &[&str]) -> String {
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or_default();
+
+    let lines: Vec<&str> = lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or_default().trim_end())
+        .collect();
+    lines.join("\n").trim_end().to_string()
+}
+
+/// Collapses runs of whitespace into single spaces.
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Finds the line of a text in a range of lines. The lines are joined, so
+/// that the text is found even if it wraps differently. If the whole text
+/// is not found, shorter prefixes of at least three words are tried.
+fn find_text(lines: &[String], range: Range<usize>, text: &str) -> Option<usize> {
+    let mut joined = String::new();
+    let mut starts = Vec::new();
+    for index in range {
+        starts.push((joined.len(), index));
+        joined.push_str(&normalize(&lines[index]));
+        joined.push(' ');
+    }
+
+    let words: Vec<&str> = text.split(' ').collect();
+    let min_words = words.len().min(3);
+    (min_words..=words.len()).rev().find_map(|len| {
+        let position = joined.find(&words[..len].join(" "))?;
+        let (_, line) = starts.iter().rev().find(|(start, _)| *start <= position)?;
+        Some(*line)
+    })
+}
+
+fn apply_sgr(params: &str, bold: &mut bool, italic: &mut bool, underline: &mut bool) {
+    for param in params.split(';') {
+        match param {
+            "" | "0" => (*bold, *italic, *underline) = (false, false, false),
+            "1" => *bold = true,
+            "3" => *italic = true,
+            "4" => *underline = true,
+            "22" => *bold = false,
+            "23" => *italic = false,
+            "24" => *underline = false,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn fragment(text: &str, emphasis: Emphasis) -> Fragment {
+        Fragment {
+            text: text.to_string(),
+            emphasis,
+        }
+    }
+
+    #[test]
+    fn test_parse_bold() {
+        let man = "COMMAND N\u{8}NA\u{8}AM\u{8}ME\u{8}E";
+        let manual = Manual::parse(man);
+
+        assert_eq!(
+            manual.lines,
+            vec![vec![
+                fragment("COMMAND ", Emphasis::None),
+                fragment("NAME", Emphasis::Bold),
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_parse_underline() {
+        let man = "_\u{8}N_\u{8}A_\u{8}M_\u{8}E";
+        let manual = Manual::parse(man);
+
+        assert_eq!(
+            manual.lines,
+            vec![vec![fragment("NAME", Emphasis::Underline)]]
+        );
+    }
+
+    #[test]
+    fn test_parse_bold_underline() {
+        let man = "_\u{8}N\u{8}N_\u{8}A\u{8}A";
+        let manual = Manual::parse(man);
+
+        assert_eq!(
+            manual.lines,
+            vec![vec![fragment("NA", Emphasis::BoldUnderline)]]
+        );
+    }
+
+    #[test]
+    fn test_anchor() {
+        let manual = Manual::plain(
+            "TAR(1)     User Commands     TAR(1)\n\nNAME\n  tar - an archiver\n\nOPTIONS\n  -c\n  -x\n\nGNU tar     TAR(1)",
+        );
+
+        let headings: Vec<_> = manual.headings().into_iter().map(|(i, _)| i).collect();
+        assert_eq!(headings, vec![2, 5]);
+
+        let anchor = manual.anchor(7);
+        assert_eq!(
+            anchor,
+            Anchor {
+                heading: String::from("OPTIONS"),
+                offset: 2,
+                text: String::from("-x"),
+            }
+        );
+        assert_eq!(manual.resolve(&anchor), Some(7));
+
+        let anchor = manual.anchor(1);
+        assert_eq!(anchor.heading, "");
+        assert_eq!(manual.resolve(&anchor), Some(1));
+
+        let unknown = Anchor {
+            heading: String::from("EXAMPLES"),
+            offset: 0,
+            text: String::new(),
+        };
+        assert_eq!(manual.resolve(&unknown), None);
+    }
+
+    #[test]
+    fn test_sections() {
+        let manual = Manual::plain(
+            "TAR(1)     User Commands     TAR(1)\n\nNAME\n  tar\n\nSYNOPSIS\n  tar -c\n\nOPTIONS\n  -c\n\nGNU tar     TAR(1)",
+        );
+
+        let sections = manual.sections(&[String::from("options"), String::from("NAME")]);
+        assert_eq!(
+            sections.plain_lines(),
+            vec!["NAME", "  tar", "", "OPTIONS", "  -c", ""]
+        );
+        assert!(manual.sections(&[String::from("EXAMPLES")]).is_empty());
+    }
+
+    #[test]
+    fn test_anchor_survives_rewrapping() {
+        let narrow = Manual::plain("NAME\n  tar - an\n  archiving utility\n\nOPTIONS\n  -c");
+        let wide = Manual::plain("NAME\n  tar - an archiving utility\n\nOPTIONS\n  -c");
+
+        let anchor = narrow.anchor(2);
+        assert_eq!(anchor.text, "archiving utility");
+        assert_eq!(wide.resolve(&anchor), Some(1));
+
+        // The section moved, but the text still exists.
+        let moved = Anchor {
+            heading: String::from("DESCRIPTION"),
+            ..narrow.anchor(5)
+        };
+        assert_eq!(wide.resolve(&moved), Some(4));
+    }
+
+    #[test]
+    fn test_footer() {
+        let manual = Manual::plain("TAR(1)    User Commands    TAR(1)\n\nNAME\n       tar\n\nGNU tar 1.35    2023-07-18    TAR(1)\n\n");
+        assert_eq!(
+            manual.footer(),
+            Some(Footer {
+                version: String::from("GNU tar 1.35"),
+                date: String::from("2023-07-18"),
+            })
+        );
+        assert_eq!(Manual::plain("NAME\n       tar").footer(), None);
+    }
+
+    #[test]
+    fn test_code_blocks() {
+        let manual = Manual::plain(
+            "TAR(1)    User Commands    TAR(1)\n\nOPTIONS\n       -A, --catenate\n              Append archives.\n       -f FILE\n              Use FILE\n\nEXAMPLES\n       Create an archive:\n\n             tar -cf archive.tar \\\n                 foo bar\n\n       List it.\n             tar -tvf archive.tar.\n\nGNU tar 1.35    2023-07-18    TAR(1)",
+        );
+        let blocks = manual.code_blocks();
+        assert_eq!(
+            blocks,
+            vec![
+                CodeBlock {
+                    heading: String::from("EXAMPLES"),
+                    lines: 11..13,
+                    text: String::from("tar -cf archive.tar \\\n    foo bar"),
+                },
+                CodeBlock {
+                    heading: String::from("EXAMPLES"),
+                    lines: 15..16,
+                    text: String::from("tar -tvf archive.tar."),
+                },
+            ]
+        );
+        // The descriptions of options are indented like examples.
+        assert!(blocks.iter().all(|block| block.heading != "OPTIONS"));
+    }
+
+    #[test]
+    fn test_section_text() {
+        let manual = Manual::plain(
+            "TAR(1)    User Commands    TAR(1)\n\nSYNOPSIS\n       tar [OPTION...]\n           [FILE]...\n\nGNU tar 1.35    2023-07-18    TAR(1)",
+        );
+        assert_eq!(
+            manual.section_text(4),
+            Some(String::from("SYNOPSIS\ntar [OPTION...]\n    [FILE]..."))
+        );
+        assert_eq!(manual.section_text(0), None);
+    }
+
+    #[test]
+    fn test_overstrike() {
+        let man = "N\u{8}NA\u{8}A x _\u{8}f_\u{8}i _\u{8}b\u{8}b\nplain";
+        let manual = Manual::parse(man);
+
+        assert_eq!(manual.overstrike(), format!("{man}\n"));
+    }
+
+    #[test]
+    fn test_parse_sgr_combined() {
+        let man = "\x1b[1;3mNAME\x1b[0m \x1b[3;4mfile\x1b[0m \x1b[1;3;4mdir\x1b[0m";
+        let manual = Manual::parse(man);
+
+        assert_eq!(
+            manual.lines,
+            vec![vec![
+                fragment("NAME", Emphasis::BoldItalic),
+                fragment(" ", Emphasis::None),
+                fragment("file", Emphasis::ItalicUnderline),
+                fragment(" ", Emphasis::None),
+                fragment("dir", Emphasis::BoldItalicUnderline),
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_parse_sgr() {
+        let man = "\x1b[1mNAME\x1b[0m \x1b[3mfile\x1b[23m";
+        let manual = Manual::parse(man);
+
+        assert_eq!(
+            manual.lines,
+            vec![vec![
+                fragment("NAME", Emphasis::Bold),
+                fragment(" ", Emphasis::None),
+                fragment("file", Emphasis::Italic),
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_parse_truncated_sgr() {
+        let man = "\x1b[1mNAME\x1b[0m file \x1b[1;3\nnext";
+        let manual = Manual::parse(man);
+
+        assert_eq!(
+            manual.lines,
+            vec![
+                vec![
+                    fragment("NAME", Emphasis::Bold),
+                    fragment(" file [1;3", Emphasis::None),
+                ],
+                vec![fragment("next", Emphasis::None)],
+            ]
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::manual::Anchor;
+use super::page::PageRef;
+use super::store;
+
+/// Vim-style marks on man pages, persisted in the data directory.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct Marks {
+    #[serde(default, rename = "mark")]
+    marks: Vec<Mark>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Mark {
+    pub(crate) page: PageRef,
+    pub(crate) letter: char,
+    /// The first visible line when the mark was set.
+    #[serde(flatten)]
+    pub(crate) anchor: Anchor,
+}
+
+impl Marks {
+    const FILE: &str = "marks.toml";
+
+    /// Loads the marks, or starts without any if the file is broken.
+    pub(crate) fn load_or_default() -> (Self, Option<anyhow::Error>) {
+        store::load_or_default(Self::FILE)
+    }
+
+    pub(crate) fn save(&self) -> Result<()> {
+        store::save(Self::FILE, self)
+    }
+
+    /// Returns the marks on a page, ordered by letter. The page must have its
+    /// section, see `with_section`, such that `tar` and `tar(1)` share marks.
+    pub(crate) fn for_page<'a>(&'a self, page: &'a PageRef) -> impl Iterator<Item = &'a Mark> {
+        self.marks.iter().filter(move |mark| mark.page == *page)
+    }
+
+    /// Sets a mark on a page, replacing the mark with the same letter.
+    pub(crate) fn set(&mut self, page: &PageRef, letter: char, anchor: Anchor) {
+        self.marks
+            .retain(|mark| mark.page != *page || mark.letter != letter);
+
+        let mark = Mark {
+            page: page.clone(),
+            letter,
+            anchor,
+        };
+        let index = self
+            .marks
+            .partition_point(|mark| (&mark.page.name, mark.letter) < (&page.name, letter));
+        self.marks.insert(index, mark);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::{with_section, Manual};
+
+    fn anchor(heading: &str, text: &str) -> Anchor {
+        Anchor {
+            heading: heading.to_string(),
+            offset: 0,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_set() {
+        let mut marks = Marks::default();
+        let tar = PageRef::new("tar", Some("1"));
+        let ls = PageRef::new("ls", Some("1"));
+
+        marks.set(&tar, 'e', anchor("EXAMPLES", "tar -cf"));
+        marks.set(&tar, 'a', anchor("OPTIONS", "--foo"));
+        marks.set(&ls, 'a', anchor("OPTIONS", "-l"));
+        marks.set(&tar, 'a', anchor("OPTIONS", "--bar"));
+
+        let letters: Vec<_> = marks.for_page(&tar).map(|m| m.letter).collect();
+        assert_eq!(letters, vec!['a', 'e']);
+        let first = marks.for_page(&tar).next().unwrap();
+        assert_eq!(first.anchor.text, "--bar");
+        assert_eq!(marks.for_page(&ls).count(), 1);
+
+        let toml = toml::to_string(&marks).unwrap();
+        let parsed: Marks = toml::from_str(&toml).unwrap();
+        assert_eq!(parsed.marks, marks.marks);
+    }
+
+    #[test]
+    fn test_without_section() {
+        let manual = Manual::plain("TAR(1)    User Commands    TAR(1)\n\nNAME\n       tar");
+        let mut marks = Marks::default();
+
+        let tar = with_section(&PageRef::new("tar", None), &manual);
+        marks.set(&tar, 'a', anchor("NAME", "tar"));
+
+        let tar = with_section(&PageRef::new("tar", Some("1")), &manual);
+        assert_eq!(marks.for_page(&tar).count(), 1);
+    }
+}
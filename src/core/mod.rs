@@ -1,18 +1,165 @@
 use lister::Lister;
 use reader::Reader;
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
 
+mod collections;
+mod diff;
+mod export;
+mod help;
+mod history;
+mod info;
 mod lister;
+mod manual;
+mod marks;
+mod notes;
+mod page;
 mod reader;
+mod store;
+mod tldr;
+
+pub(crate) use collections::{Collections, FAVORITES};
+pub(crate) use diff::{hunks, Change, Diff, DiffLine};
+pub(crate) use export::Format;
+pub(crate) use help::HelpFallback;
+pub(crate) use history::History;
+pub(crate) use info::{IndexEntry, InfoManual, Link, Reference};
+pub(crate) use lister::{Entry, SECTIONS};
+
+pub(crate) use manual::{Anchor, CodeBlock, Emphasis, Footer, Manual};
+pub(crate) use marks::{Mark, Marks};
+pub(crate) use notes::{Note, Notes};
+pub(crate) use page::{PageRef, Source};
+pub(crate) use reader::Diagnostic;
 
 pub(crate) fn load_section(section: String) -> anyhow::Result<Vec<String>> {
     Lister::list_section(section)
 }
 
-pub(crate) fn read_command(command: &str, width: &str) -> anyhow::Result<String> {
-    let manual = Reader::read(command, width)?;
+/// Lists the pages of a section, or of all sections.
+pub(crate) fn list_pages(section: Option<&str>) -> anyhow::Result<Vec<Entry>> {
+    Lister::entries(".", section)
+}
+
+/// Searches the names and descriptions of the pages, like `man -k`.
+pub(crate) fn search_pages(query: &str, section: Option<&str>) -> anyhow::Result<Vec<Entry>> {
+    Lister::entries(query, section)
+}
+
+pub(crate) fn read_page(page: &PageRef, width: &str) -> anyhow::Result<Manual> {
+    // The section of a listing can be more general than the section
+    // of the page, e.g. `3` for `3ssl`, thus retry without the section.
+    let manual = Reader::read(page, width).or_else(|err| match page.section {
+        Some(_) => Reader::read(&PageRef::new(&page.name, None), width),
+        None => Err(err),
+    })?;
+
     if manual.is_empty() {
-        Reader::read(&command.to_lowercase(), width)
+        let page = PageRef::new(&page.name.to_lowercase(), page.section.as_deref());
+        Reader::read(&page, width)
     } else {
         Ok(manual)
     }
 }
+
+/// Renders a man page file, e.g. `./docs/foo.1` or `foo.1.gz`.
+pub(crate) fn read_file(path: &Path, width: &str) -> anyhow::Result<Manual> {
+    Reader::read_file(path, width).map(|(manual, _)| manual)
+}
+
+/// Returns the source file of a page, e.g. `/usr/share/man/man1/tar.1.gz`.
+pub(crate) fn page_source(page: &PageRef) -> anyhow::Result<PathBuf> {
+    Reader::locate(page)
+}
+
+/// Reads a text file, e.g. the source of a page, decompressing it if it ends with `.gz`.
+pub(crate) fn read_text(path: &Path) -> anyhow::Result<String> {
+    info::read(path)
+}
+
+/// Renders a man page file and returns the warnings of the formatter,
+/// e.g. about unknown macros.
+pub(crate) fn check_file(path: &Path, width: &str) -> anyhow::Result<(Manual, Vec<Diagnostic>)> {
+    Reader::read_file(path, width)
+}
+
+/// Parses a page that `man` piped to mantui as its `MANPAGER`. The page is
+/// named by `MAN_PN`, e.g. `ls(1)`, or else by the header of the page.
+pub(crate) fn read_piped(input: &[u8], name: Option<&str>) -> (Option<PageRef>, Manual) {
+    let manual = Manual::parse(&String::from_utf8_lossy(input));
+    let page = name.and_then(|name| name.parse().ok()).or_else(|| {
+        let header = export::header_page(&manual)?;
+        Some(PageRef::new(
+            &header.name.to_lowercase(),
+            header.section.as_deref(),
+        ))
+    });
+    (page, manual)
+}
+
+/// Shows the `--help` output of a command without a man page, if the
+/// configuration allows to run it. Returns the command that printed it, e.g.
+/// `tar -h` or `help cd`, along with the manual.
+pub(crate) fn read_help(name: &str, width: &str) -> anyhow::Result<(String, Manual)> {
+    help::read(name, width)
+}
+
+/// Configures the `--help` fallback for commands without a man page.
+pub(crate) fn set_help_fallback(config: HelpFallback) {
+    help::configure(config);
+}
+
+/// Returns the names of the commands with a page in the local tldr cache.
+pub(crate) fn tldr_names() -> HashSet<String> {
+    tldr::names()
+}
+
+/// Reads the tldr page of a command from the local cache.
+pub(crate) fn read_tldr(name: &str) -> Option<Manual> {
+    tldr::read(name)
+}
+
+/// Returns the page with its section, which may only be known from the
+/// header of its manual, e.g. `tar(1)` for `tar`.
+pub(crate) fn with_section(page: &PageRef, manual: &Manual) -> PageRef {
+    let mut page = page.clone();
+    if page.section.is_none() {
+        page.section = export::header_page(manual)
+            .and_then(|header| header.section)
+            .map(|section| section.to_lowercase());
+    }
+    page
+}
+
+/// Renders a page and converts it to a format. Returns the page with its
+/// section, which may only be known from the header, and the exported text.
+pub(crate) fn export_page(page: &PageRef, format: Format) -> anyhow::Result<(PageRef, String)> {
+    let manual = read_page(page, format.width())?;
+    let page = with_section(page, &manual);
+    let text = export::export(&manual, &page, format);
+    Ok((page, text))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_read_piped() {
+        let input =
+            b"TAR(1)    GNU TAR Manual    TAR(1)\n\nNAME\n       tar - an archiving utility\n";
+
+        let (page, manual) = read_piped(input, None);
+        assert_eq!(page, Some(PageRef::new("tar", Some("1"))));
+        assert_eq!(manual.plain_lines()[2], "NAME");
+
+        let (page, _) = read_piped(input, Some("gtar(1)"));
+        assert_eq!(page, Some(PageRef::new("gtar", Some("1"))));
+
+        let (page, manual) = read_piped(b"some text\n", None);
+        assert_eq!(page, None);
+        assert_eq!(manual.plain_lines()[0], "some text");
+    }
+}
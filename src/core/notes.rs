@@ -0,0 +1,109 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::manual::Anchor;
+use super::page::PageRef;
+use super::store;
+
+/// Personal notes on man pages, persisted in the data directory.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct Notes {
+    #[serde(default, rename = "note")]
+    notes: Vec<Note>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Note {
+    pub(crate) page: PageRef,
+    /// The annotated line.
+    #[serde(flatten)]
+    pub(crate) anchor: Anchor,
+    pub(crate) note: String,
+}
+
+impl Notes {
+    const FILE: &str = "notes.toml";
+
+    /// Loads the notes, or starts without any if the file is broken.
+    pub(crate) fn load_or_default() -> (Self, Option<anyhow::Error>) {
+        store::load_or_default(Self::FILE)
+    }
+
+    pub(crate) fn save(&self) -> Result<()> {
+        store::save(Self::FILE, self)
+    }
+
+    /// Returns the notes on a page. The page must have its section, see
+    /// `with_section`, such that `tar` and `tar(1)` share notes.
+    pub(crate) fn for_page<'a>(&'a self, page: &'a PageRef) -> impl Iterator<Item = &'a Note> {
+        self.notes.iter().filter(move |note| note.page == *page)
+    }
+
+    /// Sets the note on a line, or removes it if the text is empty.
+    pub(crate) fn set(&mut self, page: &PageRef, anchor: &Anchor, text: &str) {
+        let text = text.trim();
+        let existing = self
+            .notes
+            .iter()
+            .position(|note| note.page == *page && note.anchor == *anchor);
+
+        match (existing, text.is_empty()) {
+            (Some(index), true) => {
+                self.notes.remove(index);
+            }
+            (Some(index), false) => self.notes[index].note = text.to_string(),
+            (None, true) => {}
+            (None, false) => self.notes.push(Note {
+                page: page.clone(),
+                anchor: anchor.clone(),
+                note: text.to_string(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::{with_section, Manual};
+
+    #[test]
+    fn test_set() {
+        let mut notes = Notes::default();
+        let page = PageRef::new("tar", Some("1"));
+        let anchor = Anchor {
+            heading: String::from("OPTIONS"),
+            offset: 4,
+            text: String::from("--foo"),
+        };
+
+        notes.set(&page, &anchor, "broken on our distro, use --bar");
+        notes.set(&page, &anchor, " use --bar ");
+        let texts: Vec<_> = notes.for_page(&page).map(|n| n.note.as_str()).collect();
+        assert_eq!(texts, vec!["use --bar"]);
+
+        let toml = toml::to_string(&notes).unwrap();
+        let parsed: Notes = toml::from_str(&toml).unwrap();
+        assert_eq!(parsed.notes, notes.notes);
+
+        notes.set(&page, &anchor, "");
+        assert_eq!(notes.for_page(&page).count(), 0);
+    }
+
+    #[test]
+    fn test_without_section() {
+        let manual = Manual::plain("TAR(1)    User Commands    TAR(1)\n\nNAME\n       tar");
+        let mut notes = Notes::default();
+        let anchor = Anchor {
+            heading: String::from("NAME"),
+            offset: 1,
+            text: String::from("tar"),
+        };
+
+        let tar = with_section(&PageRef::new("tar", Some("1")), &manual);
+        notes.set(&tar, &anchor, "use --bar");
+
+        let tar = with_section(&PageRef::new("tar", None), &manual);
+        assert_eq!(notes.for_page(&tar).count(), 1);
+    }
+}
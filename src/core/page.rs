@@ -0,0 +1,170 @@
+use anyhow::{anyhow, Error, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use super::manual::Manual;
+use super::{read_file, read_page};
+
+/// A reference to a man page, e.g. `ssh_config(5)` or `tar`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub(crate) struct PageRef {
+    pub(crate) name: String,
+    pub(crate) section: Option<String>,
+}
+
+impl PageRef {
+    pub(crate) fn new(name: &str, section: Option<&str>) -> Self {
+        Self {
+            name: name.trim().to_string(),
+            section: section.map(|section| section.trim().to_string()),
+        }
+    }
+}
+
+impl FromStr for PageRef {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(anyhow!("empty page name"));
+        }
+
+        if let Some(stripped) = s.strip_suffix(')') {
+            if let Some(pos) = stripped.rfind('(') {
+                let (name, section) = (&stripped[..pos], &stripped[pos + 1..]);
+                if !name.trim().is_empty() && !section.is_empty() {
+                    return Ok(Self::new(name, Some(section)));
+                }
+            }
+        }
+
+        Ok(Self::new(s, None))
+    }
+}
+
+impl TryFrom<String> for PageRef {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<PageRef> for String {
+    fn from(value: PageRef) -> Self {
+        value.to_string()
+    }
+}
+
+impl fmt::Display for PageRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.section {
+            Some(section) => write!(f, "{}({section})", self.name),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+/// A man page or a man page file, e.g. `/usr/local/share/man/man1/tar.1.gz`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Source {
+    Page(PageRef),
+    File(PathBuf),
+}
+
+impl Source {
+    pub(crate) fn read(&self, width: &str) -> Result<Manual> {
+        match self {
+            Source::Page(page) => read_page(page, width),
+            Source::File(path) => read_file(path, width),
+        }
+    }
+}
+
+impl FromStr for Source {
+    type Err = Error;
+
+    /// Paths contain a slash or end like a man page file, e.g. `foo.1`,
+    /// `foo.man` or `foo.1.gz`. Anything else is a page, even if a file of
+    /// that name happens to be in the current directory.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(anyhow!("empty page name"));
+        }
+
+        let extension = Path::new(s).extension().and_then(|e| e.to_str());
+        let is_path = s.contains('/')
+            || matches!(
+                extension,
+                Some("1" | "2" | "3" | "4" | "5" | "6" | "7" | "8" | "9" | "man" | "gz")
+            );
+        if is_path {
+            Ok(Source::File(PathBuf::from(s)))
+        } else {
+            Ok(Source::Page(s.parse()?))
+        }
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Page(page) => write!(f, "{page}"),
+            Source::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_parse_page_ref() {
+        let page: PageRef = "ssh_config(5)".parse().unwrap();
+        assert_eq!(page, PageRef::new("ssh_config", Some("5")));
+        assert_eq!(page.to_string(), "ssh_config(5)");
+
+        let page: PageRef = "printf(1p)".parse().unwrap();
+        assert_eq!(page, PageRef::new("printf", Some("1p")));
+
+        let page: PageRef = "tar".parse().unwrap();
+        assert_eq!(page, PageRef::new("tar", None));
+
+        assert!("".parse::<PageRef>().is_err());
+    }
+
+    #[test]
+    fn test_parse_source() {
+        assert_eq!(
+            "printf(1p)".parse::<Source>().unwrap(),
+            Source::Page(PageRef::new("printf", Some("1p")))
+        );
+        assert_eq!(
+            "./docs/foo.1".parse::<Source>().unwrap(),
+            Source::File(PathBuf::from("./docs/foo.1"))
+        );
+        assert_eq!(
+            "foo.1.gz".parse::<Source>().unwrap(),
+            Source::File(PathBuf::from("foo.1.gz"))
+        );
+    }
+
+    #[test]
+    fn test_parse_source_file_in_current_dir() {
+        let name = "mantui-test-source";
+        fs::write(name, "").unwrap();
+        let source = name.parse::<Source>();
+        fs::remove_file(name).unwrap();
+
+        assert_eq!(source.unwrap(), Source::Page(PageRef::new(name, None)));
+    }
+}
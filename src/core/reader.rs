@@ -1,29 +1,128 @@
 use anyhow::{anyhow, Result};
-use std::process::Command;
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+use super::manual::Manual;
+use super::page::PageRef;
 
 pub(crate) struct Reader;
 
+/// A warning from rendering a man page file, e.g. about an unknown macro.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Diagnostic {
+    /// The line in the source file, if the warning names one.
+    pub(crate) line: Option<usize>,
+    pub(crate) message: String,
+}
+
+impl Diagnostic {
+    /// Parses the warnings of groff or mandoc, e.g.
+    /// `troff:<standard input>:12: warning: macro 'XY' not defined`.
+    fn parse(output: &str) -> Vec<Self> {
+        output
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let parts: Vec<&str> = line.split(':').collect();
+                let numeric = |part: &&str| part.trim().parse::<usize>().is_ok();
+                let Some(position) = parts.iter().position(numeric) else {
+                    return Self {
+                        line: None,
+                        message: line.trim().to_string(),
+                    };
+                };
+
+                // mandoc adds the column after the line.
+                let rest: Vec<&str> = parts[position..]
+                    .iter()
+                    .skip_while(|part| numeric(part))
+                    .copied()
+                    .collect();
+                Self {
+                    line: parts[position].trim().parse().ok(),
+                    message: rest.join(":").trim().to_string(),
+                }
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {line}: {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
 impl Reader {
-    pub(super) fn read(command: &str, width: &str) -> Result<String> {
+    pub(super) fn read(page: &PageRef, width: &str) -> Result<Manual> {
+        let (name, section) = (page.name.as_str(), page.section.as_deref());
         let output = if cfg!(target_os = "macos") {
-            command_macos(command, width)
-                .or_else(|_| command_macos(command.to_lowercase(), width))?
+            command_macos(name, section, width)
+                .or_else(|_| command_macos(name.to_lowercase(), section, width))?
         } else {
-            command_linux(command, width)
-                .or_else(|_| command_linux(&command.to_lowercase(), width))
-                .or_else(|_| command_macos(command, width))
-                .or_else(|_| command_macos(command.to_lowercase(), width))?
+            command_linux(name, section, width)
+                .or_else(|_| command_linux(&name.to_lowercase(), section, width))
+                .or_else(|_| command_macos(name, section, width))
+                .or_else(|_| command_macos(name.to_lowercase(), section, width))?
         };
 
-        let ansi = man_to_ansi(&output);
+        Ok(Manual::parse(&output))
+    }
 
-        Ok(ansi)
+    /// Renders a man page file. Returns the warnings of the formatter as well.
+    pub(super) fn read_file(path: &Path, width: &str) -> Result<(Manual, Vec<Diagnostic>)> {
+        if !path.is_file() {
+            return Err(anyhow!("no such file: {}", path.display()));
+        }
+
+        // Not every `man` knows `--warnings`, thus retry without.
+        let output =
+            command_file(path, width, true).or_else(|_| command_file(path, width, false))?;
+        let manual = Manual::parse(&String::from_utf8_lossy(&output.stdout));
+        let diagnostics = Diagnostic::parse(&String::from_utf8_lossy(&output.stderr));
+        Ok((manual, diagnostics))
+    }
+
+    /// Returns the source file of a page, e.g. `/usr/share/man/man1/tar.1.gz`.
+    pub(super) fn locate(page: &PageRef) -> Result<PathBuf> {
+        let output = Command::new("man")
+            .arg("-w")
+            .args(page.section.as_deref())
+            .arg(&page.name)
+            .env("LC_ALL", "C")
+            .output()?;
+
+        if !output.status.success() {
+            return Err(anyhow!("command failed: {}", output.status));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let path = stdout.lines().next().map(str::trim).unwrap_or_default();
+        if path.is_empty() || !Path::new(path).is_file() {
+            return Err(anyhow!("no source file for {page}"));
+        }
+        Ok(PathBuf::from(path))
     }
 }
 
-fn command_macos<S: AsRef<str>>(command: S, width: &str) -> Result<String> {
-    let output = Command::new("man")
-        .arg(strip_section(command.as_ref()))
+fn command_file(path: &Path, width: &str, warnings: bool) -> Result<Output> {
+    // `man -l` reads a local file on Linux, on macOS a path is enough.
+    let mut command = Command::new("man");
+    if !cfg!(target_os = "macos") {
+        command.args(["-t", "-Tascii"]);
+        if warnings {
+            command.arg("--warnings=mac,font,escape,delim");
+        }
+        command.arg("-l");
+    }
+    let output = command
+        .arg(path)
         .env("MANWIDTH", width)
         .env("LC_ALL", "C")
         .output()?;
@@ -32,14 +131,13 @@ fn command_macos<S: AsRef<str>>(command: S, width: &str) -> Result<String> {
         return Err(anyhow!("command failed: {}", output.status));
     }
 
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    Ok(output)
 }
 
-fn command_linux(command: &str, width: &str) -> Result<String> {
+fn command_macos<S: AsRef<str>>(name: S, section: Option<&str>, width: &str) -> Result<String> {
     let output = Command::new("man")
-        .arg("-t")
-        .arg("-Tascii")
-        .arg(strip_section(command))
+        .args(section)
+        .arg(name.as_ref())
         .env("MANWIDTH", width)
         .env("LC_ALL", "C")
         .output()?;
@@ -51,80 +149,21 @@ fn command_linux(command: &str, width: &str) -> Result<String> {
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
-fn strip_section(command: &str) -> String {
-    if command.ends_with(')') {
-        if let Some(pos) = command.rfind('(') {
-            return command[..pos].trim().to_string();
-        }
-    }
-    command.trim().to_string()
-}
-
-const ANSI_RESET: &str = "\x1B[0m";
-#[allow(unused)]
-const ANSI_BOLD: &str = "\x1B[1m";
-#[allow(unused)]
-const ANSI_ITALIC: &str = "\x1B[3m";
-#[allow(unused)]
-const ANSI_UNDERLINE: &str = "\x1B[4m";
-
-const ANSI_ORANGE: &str = "\x1B[38;2;248;154;99m";
-
-const ANSI_PURPLE: &str = "\x1b[38;2;152;120;209m";
-
-enum Format {
-    None,
-    Bold,
-    Underline,
-}
+fn command_linux(name: &str, section: Option<&str>, width: &str) -> Result<String> {
+    let output = Command::new("man")
+        .arg("-t")
+        .arg("-Tascii")
+        .args(section)
+        .arg(name)
+        .env("MANWIDTH", width)
+        .env("LC_ALL", "C")
+        .output()?;
 
-// Bold: c\u{8}c
-// UnderLine: _\u{8}c
-fn man_to_ansi(input: &str) -> String {
-    let mut result = String::new();
-    let mut chars = input.chars().peekable();
-
-    let mut active_format = Format::None;
-
-    while let Some(curr) = chars.next() {
-        match (curr, chars.peek()) {
-            (ch, Some('\u{8}')) => {
-                if ch == '_' {
-                    active_format = Format::Underline;
-                } else {
-                    active_format = Format::Bold;
-                }
-            }
-            ('\u{8}', Some(ch)) => {
-                match active_format {
-                    Format::Bold => {
-                        result.push_str(&formatted_char(*ch, ANSI_PURPLE));
-                    }
-                    Format::Underline => {
-                        result.push_str(&formatted_char(*ch, ANSI_ORANGE));
-                    }
-                    Format::None => {}
-                }
-                chars.next();
-            }
-            (ch, _) => result.push(ch),
-        }
+    if !output.status.success() {
+        return Err(anyhow!("command failed: {}", output.status));
     }
 
-    remove_redundant_ansi(&mut result);
-
-    result = result.replace('`', "'");
-
-    result
-}
-
-fn formatted_char(ch: char, format: &str) -> String {
-    format!("{format}{ch}{ANSI_RESET}")
-}
-
-fn remove_redundant_ansi(result: &mut String) {
-    *result = result.replace(&format!("{ANSI_RESET}{ANSI_PURPLE}"), "");
-    *result = result.replace(&format!("{ANSI_RESET}{ANSI_ORANGE}"), "");
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
 #[cfg(test)]
@@ -132,21 +171,32 @@ mod test {
     use super::*;
 
     #[test]
-    fn test_man_to_ansi() {
-        let man = "COMMAND N\u{8}NA\u{8}AM\u{8}ME\u{8}E";
-        let ansi = man_to_ansi(man);
+    fn test_parse_diagnostics() {
+        let output = "troff:<standard input>:12: warning: macro 'XY' not defined\n\
+            mandoc: foo.1:7:2: WARNING: skipping paragraph macro: PP after SH\n\
+            \n\
+            man: can't set the locale\n";
 
         assert_eq!(
-            ansi,
-            String::from(format!("COMMAND {ANSI_PURPLE}NAME{ANSI_RESET}"))
+            Diagnostic::parse(output),
+            vec![
+                Diagnostic {
+                    line: Some(12),
+                    message: String::from("warning: macro 'XY' not defined"),
+                },
+                Diagnostic {
+                    line: Some(7),
+                    message: String::from("WARNING: skipping paragraph macro: PP after SH"),
+                },
+                Diagnostic {
+                    line: None,
+                    message: String::from("man: can't set the locale"),
+                },
+            ]
+        );
+        assert_eq!(
+            Diagnostic::parse(output)[0].to_string(),
+            "line 12: warning: macro 'XY' not defined"
         );
-    }
-
-    #[test]
-    fn test_man_to_ansi_underline() {
-        let man = "_\u{8}N_\u{8}A_\u{8}M_\u{8}E";
-        let ansi = man_to_ansi(man);
-
-        assert_eq!(ansi, String::from(format!("{ANSI_ORANGE}NAME{ANSI_RESET}")));
     }
 }
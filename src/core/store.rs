@@ -0,0 +1,56 @@
+use anyhow::{anyhow, Context, Error, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{fs, path::PathBuf};
+
+use crate::config::data_dir;
+
+fn path(file: &str) -> Result<PathBuf> {
+    data_dir()
+        .map(|dir| dir.join(file))
+        .ok_or_else(|| anyhow!("failed to determine the data directory"))
+}
+
+/// Reads a TOML file from the data directory. A missing file yields the default value.
+pub(crate) fn load<T: DeserializeOwned + Default>(file: &str) -> Result<T> {
+    let path = path(file)?;
+    if !path.exists() {
+        return Ok(T::default());
+    }
+
+    let content = fs::read_to_string(&path)?;
+    toml::from_str(&content).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+/// Reads a TOML file like [`load`], but starts with the default value if the
+/// file can't be read. A broken file is moved to `<file>.bak` first, so that
+/// saving the default value doesn't overwrite it.
+pub(crate) fn load_or_default<T: DeserializeOwned + Default>(file: &str) -> (T, Option<Error>) {
+    let err = match load(file) {
+        Ok(value) => return (value, None),
+        Err(err) => err,
+    };
+
+    let backup = format!("{file}.bak");
+    let moved = path(file).and_then(|path| Ok(fs::rename(&path, path.with_file_name(&backup))?));
+    let err = match moved {
+        Ok(()) => err.context(format!("kept the broken file as {backup}")),
+        Err(_) => err,
+    };
+    (T::default(), Some(err))
+}
+
+/// Writes a TOML file to the data directory.
+pub(crate) fn save<T: Serialize>(file: &str, value: &T) -> Result<()> {
+    let path = path(file)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    // Write to a temporary file first, so that a failed write
+    // does not leave a truncated file behind.
+    let tmp = path.with_extension("toml.tmp");
+    fs::write(&tmp, toml::to_string(value)?)?;
+    fs::rename(&tmp, &path)?;
+
+    Ok(())
+}
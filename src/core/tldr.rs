@@ -0,0 +1,139 @@
+use std::{collections::HashSet, env, fs, path::PathBuf};
+
+use super::manual::{Emphasis, Manual};
+
+/// The platforms of the tldr pages, searched in order.
+const PLATFORMS: &[&str] = if cfg!(target_os = "macos") {
+    &["osx", "common"]
+} else {
+    &["linux", "common"]
+};
+
+/// Returns the pages directory of the local tldr cache, i.e. `$TLDR_CACHE_DIR/pages`
+/// or `~/.cache/tldr/pages`. Pages are only read from the cache, never downloaded.
+fn pages_dir() -> Option<PathBuf> {
+    let cache = env::var_os("TLDR_CACHE_DIR")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            env::var_os("XDG_CACHE_HOME")
+                .filter(|dir| !dir.is_empty())
+                .map(|dir| PathBuf::from(dir).join("tldr"))
+        })
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache/tldr")))?;
+    Some(cache.join("pages"))
+}
+
+/// Returns the names of the commands with a tldr page, in lowercase like the
+/// files that `read` looks up.
+pub(crate) fn names() -> HashSet<String> {
+    let Some(dir) = pages_dir() else {
+        return HashSet::new();
+    };
+
+    PLATFORMS
+        .iter()
+        .filter_map(|platform| fs::read_dir(dir.join(platform)).ok())
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().to_string_lossy().to_string();
+            name.strip_suffix(".md").map(str::to_lowercase)
+        })
+        .collect()
+}
+
+/// Reads the tldr page of a command, if it is in the cache.
+pub(crate) fn read(name: &str) -> Option<Manual> {
+    let dir = pages_dir()?;
+    let file = format!("{}.md", name.to_lowercase());
+    PLATFORMS
+        .iter()
+        .find_map(|platform| fs::read_to_string(dir.join(platform).join(&file)).ok())
+        .map(|page| parse(&page))
+}
+
+/// Converts a tldr page to a manual: the title becomes a heading, the
+/// examples are indented, and the placeholders of the commands, e.g.
+/// `{{path/to/file}}`, are underlined like the arguments of a man page.
+fn parse(page: &str) -> Manual {
+    let mut manual = Manual::default();
+    for text in page.lines().map(str::trim_end) {
+        manual.lines.push(Vec::new());
+        if let Some(title) = text.strip_prefix("# ") {
+            push(&mut manual, &title.to_uppercase(), Emphasis::Bold);
+        } else if let Some(description) = text.strip_prefix('>') {
+            push(
+                &mut manual,
+                &format!("       {}", description.trim()),
+                Emphasis::None,
+            );
+        } else if let Some(example) = text.strip_prefix("- ") {
+            push(&mut manual, &format!("   {example}"), Emphasis::None);
+        } else if let Some(command) = text.strip_prefix('`').and_then(|t| t.strip_suffix('`')) {
+            push(&mut manual, "       ", Emphasis::None);
+            push_command(&mut manual, command);
+        } else {
+            push(&mut manual, text, Emphasis::None);
+        }
+    }
+    manual
+}
+
+/// Appends text to the last line, empty text leaves the line as it is.
+fn push(manual: &mut Manual, text: &str, emphasis: Emphasis) {
+    if !text.is_empty() {
+        manual.push(text, emphasis);
+    }
+}
+
+fn push_command(manual: &mut Manual, mut command: &str) {
+    while let Some(start) = command.find("{{") {
+        let Some(end) = command[start..].find("}}").map(|end| start + end) else {
+            break;
+        };
+        push(manual, &command[..start], Emphasis::Bold);
+        push(manual, &command[start + 2..end], Emphasis::Underline);
+        command = &command[end + 2..];
+    }
+    push(manual, command, Emphasis::Bold);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let page = "# tar\n\n> Archiving utility.\n\n- Create an archive:\n\n`tar cf {{target.tar}} {{file}}`\n";
+        let manual = parse(page);
+
+        assert_eq!(
+            manual.plain_lines(),
+            vec![
+                "TAR",
+                "",
+                "       Archiving utility.",
+                "",
+                "   Create an archive:",
+                "",
+                "       tar cf target.tar file",
+            ]
+        );
+        assert_eq!(manual.headings(), vec![(0, String::from("TAR"))]);
+
+        let command: Vec<_> = manual.lines[6]
+            .iter()
+            .map(|fragment| (fragment.text.as_str(), fragment.emphasis))
+            .collect();
+        assert_eq!(
+            command,
+            vec![
+                ("       ", Emphasis::None),
+                ("tar cf ", Emphasis::Bold),
+                ("target.tar", Emphasis::Underline),
+                (" ", Emphasis::Bold),
+                ("file", Emphasis::Underline),
+            ]
+        );
+    }
+}
@@ -1,17 +1,64 @@
 use args::Args;
 use clap::Parser;
-use ui::{App, Theme, THEME};
+use config::Config;
+use core::{set_help_fallback, Source};
+use std::{
+    io::{self, IsTerminal},
+    process::ExitCode,
+};
+use ui::{
+    print, read_stdin, set_clipboard, set_status_fields, set_theme, App, ColorSupport, Navigation,
+    StatusField, ThemeSelection,
+};
 
 mod args;
+mod cli;
+mod config;
 mod core;
 mod ui;
 
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
+async fn main() -> anyhow::Result<ExitCode> {
     let args = Args::parse();
+    if let Some(command) = args.subcommand {
+        return Ok(cli::run(command));
+    }
 
-    THEME.get_or_init(|| Theme::init(&args));
+    let (config, config_error) = Config::load();
+    set_help_fallback(config.help_fallback);
+    set_clipboard(config.clipboard);
+    set_status_fields(
+        config
+            .status_bar
+            .unwrap_or_else(|| StatusField::ALL.to_vec()),
+    );
 
-    App::run(args.command)?;
-    Ok(())
+    let colors = config.colors.unwrap_or_else(ColorSupport::detect);
+    let theme = ThemeSelection::new(args.theme.or(config.theme), args.transparent, colors);
+    set_theme(theme.build()?);
+
+    let initial = match args.diff.as_deref() {
+        Some([old, new]) => vec![Navigation::Diff(old.clone(), new.clone())],
+        // As the `MANPAGER`, `man` pipes the rendered page to stdin.
+        _ if args.commands.is_empty() && !io::stdin().is_terminal() => {
+            vec![Navigation::Piped(read_stdin()?)]
+        }
+        _ => {
+            let sources = args
+                .commands
+                .iter()
+                .map(|command| command.parse::<Source>())
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            if args.print {
+                if let Some(err) = &config_error {
+                    eprintln!("Warning: {err:#}");
+                }
+                print(&sources, args.width, &args.section)?;
+                return Ok(ExitCode::SUCCESS);
+            }
+            Navigation::open_pages(sources, args.watch)
+        }
+    };
+    App::run(initial, theme, config_error)?;
+    Ok(ExitCode::SUCCESS)
 }
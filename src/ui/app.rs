@@ -1,23 +1,32 @@
 use anyhow::Result;
-use ratatui::widgets::StatefulWidgetRef;
-use ratatui::{
-    crossterm::event::{KeyCode, KeyModifiers},
-    prelude::*,
-};
+use chrono::Local;
+use ratatui::prelude::*;
+use ratatui::widgets::{Clear, StatefulWidgetRef};
 use std::{
+    collections::HashSet,
     marker::PhantomData,
-    sync::{mpsc, Arc, Mutex},
+    mem,
+    path::PathBuf,
+    sync::{mpsc, Arc, Mutex, OnceLock},
     thread::{self},
-    time::Duration,
+    time::{Duration, Instant},
 };
 use uuid::Uuid;
 
-use crate::core::load_section;
+use crate::core::{
+    load_section, tldr_names, Collections, History, Marks, Notes, PageRef, Source, FAVORITES,
+};
 
 use super::events::{EventController, IStatefulWidget};
-use super::pages::{ReaderPage, ReaderPageState};
+use super::keymap::{self, Action, Context};
+use super::pages::{DiffPage, DiffPageState, ReaderPage, ReaderPageState};
+use super::popups::{Popup, PopupView, ResumePrompt};
+use super::tabs::{Tab, TabBar};
+use super::theme::{get_theme, ThemeSelection};
 use super::{
-    events::{spawn_event_loop, Event, InternalEvent},
+    clipboard::Clipboard,
+    events::{pause_events, resume_events, spawn_event_loop, Event, InternalEvent},
+    external::External,
     pages::{HomePage, HomePageState, ListPage, ListPageState},
     terminal::Terminal,
 };
@@ -29,51 +38,148 @@ pub struct App<'a> {
 pub(crate) enum Navigation {
     List,
     Reader,
+    /// Opens a page from the history or the command line
+    /// and offers to resume reading where it stopped.
+    Resume(PageRef),
+    /// Opens a page in a new tab.
+    NewTab(PageRef),
+    /// Compares two pages or files.
+    Diff(Source, Source),
+    /// Shows a page piped to stdin as it arrives in chunks.
+    Piped(mpsc::Receiver<Vec<u8>>),
+    /// Opens a man page file, e.g. `./docs/foo.1`.
+    File {
+        path: PathBuf,
+        /// Renders the file again whenever it changes.
+        watch: bool,
+        new_tab: bool,
+    },
+    /// Opens the info manual of a program in a new tab.
+    Info(String),
 }
 
 impl Navigation {
-    pub(crate) fn navigate_to(to: &Self, app_state: &mut AppState, controller: &EventController) {
+    /// Opens the pages and files given on the command line, each in its own tab.
+    /// Resuming is offered for the first one.
+    pub(crate) fn open_pages(sources: Vec<Source>, watch: bool) -> Vec<Self> {
+        sources
+            .into_iter()
+            .enumerate()
+            .map(|(index, source)| match (index, source) {
+                (0, Source::Page(page)) => Navigation::Resume(page),
+                (_, Source::Page(page)) => Navigation::NewTab(page),
+                (index, Source::File(path)) => Navigation::File {
+                    path,
+                    watch,
+                    new_tab: index > 0,
+                },
+            })
+            .collect()
+    }
+
+    pub(crate) fn navigate_to(to: Self, app_state: &mut AppState, controller: &EventController) {
         match to {
             Navigation::List => {
-                let state = ListPageState::new(app_state);
-                app_state.active_state = ActiveState::List(state);
-
-                let page = IStatefulWidget::new(ListPage::new(controller), controller);
-                app_state.active_page = ActiveWidget::List(page);
+                app_state.leave_reader();
+
+                let state = match app_state.list.take() {
+                    Some(mut state) => {
+                        state.refresh(app_state);
+                        state
+                    }
+                    None => ListPageState::new(app_state),
+                };
+                app_state.replace_tab(ActiveState::List(state), controller);
             }
             Navigation::Reader => {
-                let ActiveState::List(old_state) = &mut app_state.active_state else {
+                let ActiveState::List(old_state) = &app_state.active_state else {
                     return;
                 };
-                if let Some(command) = old_state.selected_command() {
-                    // Store the selected command for later in case we navigate
-                    // back again from the reader to the list
-                    app_state.selected_command = old_state.selected_command_index();
-                    app_state.selected_section = old_state.selected_section_index();
-                    app_state.command_search = old_state.command_search();
-                    app_state.loaded_commands = old_state.loaded_commands();
-
-                    let state = ReaderPageState::new(&command, old_state.page_width());
-                    app_state.active_state = ActiveState::Read(state);
-
-                    let page = IStatefulWidget::new(ReaderPage::new(controller), controller);
-                    app_state.active_page = ActiveWidget::Read(page);
+                if let Some(page) = old_state.selected_page() {
+                    let state = ReaderPageState::new(&page, app_state.width);
+                    let state = open_reader(state, false, app_state);
+                    app_state.replace_tab(state, controller);
+                }
+            }
+            Navigation::Resume(page) => {
+                app_state.leave_reader();
+                let state = ReaderPageState::new(&page, app_state.width);
+                let state = open_reader(state, true, app_state);
+                app_state.replace_tab(state, controller);
+            }
+            Navigation::NewTab(page) => {
+                app_state.leave_reader();
+                let state = ReaderPageState::new(&page, app_state.width);
+                let state = open_reader(state, false, app_state);
+                app_state.add_tab(state, controller);
+            }
+            Navigation::Diff(old, new) => {
+                app_state.leave_reader();
+                let state = DiffPageState::new(old, new, app_state.width);
+                app_state.replace_tab(ActiveState::Diff(state), controller);
+            }
+            Navigation::File {
+                path,
+                watch,
+                new_tab,
+            } => {
+                let state = ReaderPageState::file(&path, watch, app_state.width);
+                let state = open_reader(state, false, app_state);
+                if new_tab {
+                    app_state.leave_reader();
+                    app_state.add_tab(state, controller);
+                } else {
+                    app_state.replace_tab(state, controller);
                 }
             }
+            Navigation::Info(name) => match ReaderPageState::info_manual(&name) {
+                Ok(state) => {
+                    app_state.leave_reader();
+                    app_state.add_tab(ActiveState::Read(state), controller);
+                }
+                Err(err) => app_state.notify(format!("{err:#}")),
+            },
+            Navigation::Piped(chunks) => {
+                let state = ReaderPageState::piped(chunks);
+                app_state.replace_tab(ActiveState::Read(state), controller);
+            }
         }
     }
 }
 
+fn open_reader(mut state: ReaderPageState, resume: bool, app_state: &mut AppState) -> ActiveState {
+    state.set_notes(&app_state.notes);
+    state.set_marks(&app_state.marks);
+
+    if let Some(page) = state.saved_page() {
+        let line = app_state
+            .history
+            .position(&page)
+            .and_then(|position| Some((state.resolve(position)?, position.heading.clone())));
+        if let Some((line, heading)) = line.filter(|(line, _)| resume && *line > 0) {
+            let prompt = ResumePrompt::new(page.clone(), heading, line);
+            app_state.popup = Some(Popup::Resume(prompt));
+        }
+
+        app_state.history.record(&page, Local::now());
+        app_state.save_history();
+    }
+
+    ActiveState::Read(state)
+}
+
 pub(crate) enum ActiveWidget {
     Home(IStatefulWidget<HomePage>),
     List(IStatefulWidget<ListPage>),
     Read(IStatefulWidget<ReaderPage>),
+    Diff(IStatefulWidget<DiffPage>),
 }
 
 pub(crate) enum ActiveState {
     Home(HomePageState),
     List(ListPageState),
     Read(ReaderPageState),
+    Diff(DiffPageState),
 }
 
 pub struct AppState {
@@ -81,11 +187,36 @@ pub struct AppState {
     pub(super) active_page: ActiveWidget,
     pub(super) active_state: ActiveState,
 
-    pub(super) selected_command: Option<usize>,
-    pub(super) selected_section: usize,
+    /// The list of the active tab while it shows another page, restored
+    /// when going back.
+    pub(super) list: Option<ListPageState>,
+    /// The pages of the first section, which new lists start with.
     pub(super) loaded_commands: Option<Vec<String>>,
 
-    pub(super) command_search: String,
+    /// The commands run from the command palette, most recent last.
+    pub(super) command_history: Vec<String>,
+
+    /// The inactive tabs, the active tab is stored in `active_page`, `active_state` and `list`.
+    pub(super) tabs: Vec<Tab>,
+    /// The index of the active tab among all tabs.
+    pub(super) active_tab: usize,
+
+    pub(super) popup: Option<Popup>,
+    pub(super) theme: ThemeSelection,
+
+    pub(super) collections: Collections,
+    pub(super) history: History,
+    pub(super) notes: Notes,
+    pub(super) marks: Marks,
+    /// The commands with a page in the local tldr cache, shared by all lists.
+    /// Set once the cache was scanned in the background.
+    pub(super) tldr: Arc<OnceLock<HashSet<String>>>,
+    /// The width of the terminal.
+    width: usize,
+    notification: Option<(String, Instant)>,
+    /// A program like `$EDITOR` to run in the terminal.
+    external: Option<External>,
+    clipboard: Clipboard,
 
     pub(crate) sx: mpsc::Sender<Event>,
     debouncer: Arc<Mutex<Uuid>>,
@@ -93,38 +224,205 @@ pub struct AppState {
 
 impl AppState {
     pub(super) fn new(
-        initial_command: Option<String>,
+        initial: Vec<Navigation>,
         initial_area: Rect,
+        theme: ThemeSelection,
+        config_error: Option<anyhow::Error>,
         controller: &EventController,
     ) -> Self {
-        let (active_page, active_state) = if let Some(command) = initial_command {
-            let page = ReaderPage::new(controller);
-            let state = ReaderPageState::new(&command, initial_area.width as usize);
-            (
-                ActiveWidget::Read(IStatefulWidget::new(page, controller)),
-                ActiveState::Read(state),
-            )
-        } else {
-            let page = HomePage {};
-            let state = HomePageState::new();
-            (
-                ActiveWidget::Home(IStatefulWidget::new(page, controller)),
-                ActiveState::Home(state),
-            )
-        };
+        // A broken data file is reported, it shouldn't keep mantui from starting.
+        let mut errors = Vec::new();
+        if let Some(err) = config_error {
+            errors.push(format!("Failed to load config: {err:#}"));
+        }
+        let collections = loaded("collections", Collections::load_or_default(), &mut errors);
+        let history = loaded("history", History::load_or_default(), &mut errors);
+        let notes = loaded("notes", Notes::load_or_default(), &mut errors);
+        let marks = loaded("marks", Marks::load_or_default(), &mut errors);
 
-        Self {
+        let mut state = Self {
             should_quit: false,
-            active_page,
-            active_state,
-            selected_command: None,
-            selected_section: 0,
+            active_page: ActiveWidget::Home(IStatefulWidget::new(HomePage {}, controller)),
+            active_state: ActiveState::Home(HomePageState::new()),
+            list: None,
             loaded_commands: None,
-            command_search: String::new(),
+            command_history: Vec::new(),
+            tabs: Vec::new(),
+            active_tab: 0,
+            popup: None,
+            theme,
+            collections,
+            history,
+            notes,
+            marks,
+            tldr: Arc::default(),
+            width: initial_area.width as usize,
+            notification: None,
+            external: None,
+            clipboard: Clipboard::new(),
             sx: controller.get_sender(),
             debouncer: Arc::new(Mutex::new(Uuid::new_v4())),
+        };
+
+        for navigation in initial {
+            Navigation::navigate_to(navigation, &mut state, controller);
+        }
+        state.switch_tab(0, controller);
+
+        if !errors.is_empty() {
+            state.notify(errors.join(", "));
+        }
+
+        let tldr = Arc::clone(&state.tldr);
+        thread::spawn(move || {
+            let _ = tldr.set(tldr_names());
+        });
+
+        state
+    }
+
+    /// Returns the pages of the list in the active tab, or of the first section.
+    pub(crate) fn list_commands(&self) -> Option<Vec<String>> {
+        let list = match &self.active_state {
+            ActiveState::List(list) => Some(list),
+            _ => self.list.as_ref(),
+        };
+        list.and_then(ListPageState::loaded_commands)
+            .or_else(|| self.loaded_commands.clone())
+    }
+
+    /// Offers to resume the page piped to stdin and records it in the history,
+    /// once all of it was read.
+    pub(crate) fn open_piped(&mut self) {
+        let state = mem::replace(
+            &mut self.active_state,
+            ActiveState::Home(HomePageState::new()),
+        );
+        self.active_state = match state {
+            ActiveState::Read(state) => open_reader(state, true, self),
+            state => state,
+        };
+    }
+
+    /// Remembers where reading stopped if the reader is open. The saved
+    /// position is kept while the user is asked whether to resume it.
+    pub(crate) fn leave_reader(&mut self) {
+        let ActiveState::Read(state) = &self.active_state else {
+            return;
+        };
+        if self.is_resume_pending() {
+            return;
+        }
+        if let Some(page) = state.saved_page() {
+            self.history.set_position(&page, state.position());
+            self.save_history();
+        }
+    }
+
+    /// Persists the notes after they were edited and updates the reader.
+    pub(crate) fn save_notes(&mut self) {
+        if let Err(err) = self.notes.save() {
+            self.notify(format!("Failed to save notes: {err:#}"));
+        }
+
+        if let ActiveState::Read(state) = &mut self.active_state {
+            state.set_notes(&self.notes);
+        }
+    }
+
+    /// Persists the marks after one was set and updates the reader.
+    pub(crate) fn save_marks(&mut self) {
+        if let Err(err) = self.marks.save() {
+            self.notify(format!("Failed to save marks: {err:#}"));
+        }
+
+        if let ActiveState::Read(state) = &mut self.active_state {
+            state.set_marks(&self.marks);
+        }
+    }
+
+    /// The width of the terminal.
+    pub(crate) fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Opens a page next to the one in the reader.
+    pub(crate) fn open_split(&mut self, page: &PageRef) {
+        let ActiveState::Read(state) = &mut self.active_state else {
+            return;
+        };
+        if let Some(page) = state.open_split(page, self.width, &self.notes, &self.marks) {
+            self.history.record(&page, Local::now());
+            self.save_history();
+        }
+    }
+
+    /// Compares the page in the reader with another page or file in a new tab.
+    pub(crate) fn open_diff(&mut self, new: Source, controller: &EventController) {
+        let ActiveState::Read(state) = &self.active_state else {
+            return;
+        };
+        let Some(old) = state.page().cloned() else {
+            return;
+        };
+
+        self.leave_reader();
+        let state = DiffPageState::new(Source::Page(old), new, self.width);
+        self.add_tab(ActiveState::Diff(state), controller);
+    }
+
+    fn save_history(&mut self) {
+        if let Err(err) = self.history.save() {
+            self.notify(format!("Failed to save history: {err:#}"));
         }
     }
+
+    /// Runs a program like `$EDITOR` in the terminal once the event is handled.
+    pub(crate) fn run_external(&mut self, external: External) {
+        self.external = Some(external);
+    }
+
+    /// Copies text to the clipboard and tells how, or that it failed.
+    pub(crate) fn copy(&mut self, text: &str) {
+        match self.clipboard.copy(text) {
+            Ok(message) => self.notify(message),
+            Err(err) => self.notify(format!("Failed to copy: {err:#}")),
+        }
+    }
+
+    /// Shows a short message in the bottom right corner for a few seconds.
+    pub(crate) fn notify(&mut self, message: impl Into<String>) {
+        self.notification = Some((message.into(), Instant::now()));
+    }
+
+    /// Persists the collections after they were edited and
+    /// refreshes the list if it shows a collection.
+    pub(crate) fn save_collections(&mut self) {
+        if let Err(err) = self.collections.save() {
+            self.notify(format!("Failed to save collections: {err:#}"));
+        }
+
+        if let ActiveState::List(state) = &mut self.active_state {
+            if let Some(section) = state.refresh_collection(&self.collections) {
+                load_commands_in_background(self, section);
+            }
+        }
+    }
+
+    /// Adds a page to the favorites or removes it again.
+    pub(crate) fn toggle_favorite(&mut self, page: Option<PageRef>) {
+        let Some(page) = page else {
+            self.notify("No page selected");
+            return;
+        };
+
+        if self.collections.toggle(FAVORITES, page.clone()) {
+            self.notify(format!("Added {page} to {FAVORITES}"));
+        } else {
+            self.notify(format!("Removed {page} from {FAVORITES}"));
+        }
+        self.save_collections();
+    }
 }
 
 impl App<'_> {
@@ -135,7 +433,13 @@ impl App<'_> {
         }
     }
 
-    pub fn run(initial_command: Option<String>) -> Result<()> {
+    /// Runs the terminal UI. A configuration that couldn't be read is
+    /// reported like a broken data file.
+    pub(crate) fn run(
+        initial: Vec<Navigation>,
+        theme: ThemeSelection,
+        config_error: Option<anyhow::Error>,
+    ) -> Result<()> {
         let mut terminal = Terminal::new()?;
         let initial_area = terminal.area();
 
@@ -143,7 +447,7 @@ impl App<'_> {
         spawn_event_loop(&controller, 100);
 
         let mut app = Self::new();
-        let mut state = AppState::new(initial_command, initial_area, &controller);
+        let mut state = AppState::new(initial, initial_area, theme, config_error, &controller);
 
         // Register global events.
         register_global_events(&controller);
@@ -157,8 +461,21 @@ impl App<'_> {
                 frame.render_stateful_widget(&mut app, frame.area(), &mut state);
             })?;
             controller.recv_and_notify(&mut state)?;
+
+            if let Some(external) = state.external.take() {
+                pause_events();
+                Terminal::stop()?;
+                let result = external.run();
+                terminal.restore()?;
+                resume_events();
+                if let Err(err) = result {
+                    state.notify(format!("{err:#}"));
+                }
+            }
         }
 
+        state.leave_all_tabs();
+
         Terminal::stop()?;
         Ok(())
     }
@@ -166,18 +483,18 @@ impl App<'_> {
 
 pub fn register_global_events(controller: &EventController) {
     controller.add_listener("main", |ctx, state| match ctx.event {
-        Event::Key(key) => {
-            if key.code == KeyCode::Char('c') && key.modifiers == KeyModifiers::CONTROL {
-                state.should_quit = true;
-            }
-        }
+        Event::Key(key) => match keymap::action(Context::Global, key) {
+            Some(Action::Quit) => state.should_quit = true,
+            Some(Action::NextTab) if !state.is_typing() => state.next_tab(ctx.controller),
+            Some(Action::PreviousTab) if !state.is_typing() => state.previous_tab(ctx.controller),
+            Some(Action::CloseTab) if !state.is_typing() => state.close_tab(ctx.controller),
+            _ => {}
+        },
         Event::Internal(InternalEvent::Loaded((commands, section))) => {
-            if let ActiveState::List(state) = &mut state.active_state {
-                if state.selected_section_index() == *section {
-                    state.set_loaded_commands(commands);
-                }
+            state.set_loaded_commands(commands, *section);
+            if *section == 0 {
+                state.loaded_commands = Some(commands.clone());
             }
-            state.loaded_commands = Some(commands.clone());
         }
         _ => {}
     });
@@ -187,6 +504,19 @@ impl StatefulWidget for &mut App<'_> {
     type State = AppState;
 
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        state.width = area.width as usize;
+
+        let area = if state.num_tabs() > 1 {
+            let [tab_bar, area] = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(1), Constraint::Min(0)])
+                .areas(area);
+            TabBar.render(tab_bar, buf, state);
+            area
+        } else {
+            area
+        };
+
         match (&state.active_page, &mut state.active_state) {
             (ActiveWidget::Home(page), ActiveState::Home(state)) => {
                 page.render_ref(area, buf, state);
@@ -197,11 +527,56 @@ impl StatefulWidget for &mut App<'_> {
             (ActiveWidget::Read(page), ActiveState::Read(state)) => {
                 page.render_ref(area, buf, state);
             }
+            (ActiveWidget::Diff(page), ActiveState::Diff(state)) => {
+                page.render_ref(area, buf, state);
+            }
             _ => {}
         }
+
+        if let Some(popup) = &mut state.popup {
+            let view = PopupView {
+                collections: &state.collections,
+                history: &state.history,
+            };
+            view.render(area, buf, popup);
+        }
+
+        match &state.notification {
+            Some((message, shown)) if shown.elapsed() < NOTIFICATION_DURATION => {
+                render_notification(message, area, buf);
+            }
+            Some(_) => state.notification = None,
+            None => {}
+        }
     }
 }
 
+const NOTIFICATION_DURATION: Duration = Duration::from_secs(3);
+
+/// Returns a loaded data file and remembers why it couldn't be read.
+fn loaded<T>(name: &str, (value, err): (T, Option<anyhow::Error>), errors: &mut Vec<String>) -> T {
+    if let Some(err) = err {
+        errors.push(format!("Failed to load {name}: {err}"));
+    }
+    value
+}
+
+fn render_notification(message: &str, area: Rect, buf: &mut Buffer) {
+    let theme = get_theme();
+
+    let width = (message.chars().count() as u16 + 2).min(area.width);
+    let area = Rect::new(
+        area.right().saturating_sub(width),
+        area.bottom().saturating_sub(1),
+        width,
+        1.min(area.height),
+    );
+    Clear.render(area, buf);
+    Line::from(format!(" {message} "))
+        .style(theme.list.selected)
+        .render(area, buf);
+}
+
 pub(crate) fn load_commands_in_background(ctx: &AppState, section: usize) {
     let uuid = Uuid::new_v4();
 
@@ -0,0 +1,256 @@
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Deserialize;
+use std::{
+    env,
+    io::{self, ErrorKind, IsTerminal, Write},
+    process::{Command, Stdio},
+    sync::RwLock,
+};
+
+/// How text is copied, configured in the `[clipboard]` table of the config file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub(crate) struct ClipboardConfig {
+    pub(crate) method: ClipboardMethod,
+    /// A command that copies its input, e.g. `wl-copy`. Detected if not set.
+    pub(crate) command: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum ClipboardMethod {
+    /// Tries the system clipboard, OSC 52 and a command. A command comes
+    /// before OSC 52 if there is a display server for it.
+    #[default]
+    Auto,
+    /// The clipboard of the desktop, which is not available over SSH.
+    System,
+    /// Escape sequences that ask the terminal to copy, also over SSH.
+    Osc52,
+    /// A command like `wl-copy` or `xclip`.
+    Command,
+}
+
+impl ClipboardMethod {
+    /// The methods to try in order. OSC 52 comes last with a display server,
+    /// since the terminal doesn't tell whether it copied the text.
+    fn methods(self, display: bool) -> &'static [Method] {
+        match self {
+            ClipboardMethod::Auto if display => &[Method::System, Method::Command, Method::Osc52],
+            ClipboardMethod::Auto => &[Method::System, Method::Osc52, Method::Command],
+            ClipboardMethod::System => &[Method::System],
+            ClipboardMethod::Osc52 => &[Method::Osc52],
+            ClipboardMethod::Command => &[Method::Command],
+        }
+    }
+}
+
+/// A way to copy text, tried by `Clipboard::copy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Method {
+    System,
+    Osc52,
+    Command,
+}
+
+impl Method {
+    fn name(self) -> &'static str {
+        match self {
+            Method::System => "the system clipboard",
+            Method::Osc52 => "OSC 52",
+            Method::Command => "a clipboard command",
+        }
+    }
+
+    /// The message after copying. The terminal doesn't answer OSC 52, thus
+    /// it only says that the text was sent.
+    fn message(self) -> &'static str {
+        match self {
+            Method::System => "Copied to the system clipboard",
+            Method::Osc52 => "Sent to the terminal with OSC 52",
+            Method::Command => "Copied with a clipboard command",
+        }
+    }
+}
+
+/// The commands that are tried if none is configured, with the variable
+/// that must be set for them to work.
+const COMMANDS: &[(Option<&str>, &str, &[&str])] = &[
+    (Some("WAYLAND_DISPLAY"), "wl-copy", &[]),
+    (Some("DISPLAY"), "xclip", &["-selection", "clipboard"]),
+    (Some("DISPLAY"), "xsel", &["--clipboard", "--input"]),
+    (None, "pbcopy", &[]),
+];
+
+static CONFIG: RwLock<Option<ClipboardConfig>> = RwLock::new(None);
+
+/// Sets how text is copied.
+pub(crate) fn set_clipboard(config: ClipboardConfig) {
+    *CONFIG.write().unwrap() = Some(config);
+}
+
+/// Copies text with the configured method, or the first one that works.
+pub(crate) struct Clipboard {
+    config: ClipboardConfig,
+    /// The system clipboard, which has to be kept open on Linux such that the
+    /// copied text can be pasted.
+    system: Option<arboard::Clipboard>,
+}
+
+impl Clipboard {
+    pub(crate) fn new() -> Self {
+        let config = CONFIG.read().unwrap().clone().unwrap_or_default();
+        let system = match config.method {
+            ClipboardMethod::Auto | ClipboardMethod::System => arboard::Clipboard::new().ok(),
+            ClipboardMethod::Osc52 | ClipboardMethod::Command => None,
+        };
+        Self { config, system }
+    }
+
+    /// Copies text and returns a message that says how it was copied.
+    pub(crate) fn copy(&mut self, text: &str) -> Result<&'static str> {
+        let display = ["WAYLAND_DISPLAY", "DISPLAY"]
+            .iter()
+            .any(|variable| env::var_os(variable).is_some_and(|value| !value.is_empty()));
+
+        let mut errors = Vec::new();
+        for &method in self.config.method.methods(display) {
+            match self.copy_with(method, text) {
+                Ok(()) => return Ok(method.message()),
+                Err(err) => errors.push(format!("{}: {err:#}", method.name())),
+            }
+        }
+        bail!("{}", errors.join(", "))
+    }
+
+    fn copy_with(&mut self, method: Method, text: &str) -> Result<()> {
+        match method {
+            Method::System => {
+                let system = self
+                    .system
+                    .as_mut()
+                    .ok_or_else(|| anyhow!("not available"))?;
+                system.set_text(text)?;
+            }
+            Method::Osc52 => {
+                let term = env::var("TERM").unwrap_or_default();
+                if !io::stdout().is_terminal() || matches!(term.as_str(), "" | "dumb" | "linux") {
+                    bail!("not supported by the terminal");
+                }
+                let mut stdout = io::stdout();
+                stdout.write_all(osc52(text, &term, env::var_os("TMUX").is_some()).as_bytes())?;
+                stdout.flush()?;
+            }
+            Method::Command => match &self.config.command {
+                Some(command) => {
+                    let mut parts = command.split_whitespace();
+                    let program = parts
+                        .next()
+                        .ok_or_else(|| anyhow!("the command is empty"))?;
+                    run(program, &parts.collect::<Vec<_>>(), text)?;
+                }
+                None => {
+                    let mut commands = COMMANDS.iter().filter(|(variable, ..)| {
+                        variable.is_none_or(|variable| env::var_os(variable).is_some())
+                    });
+                    let copied =
+                        commands.find_map(|(_, program, args)| match run(program, args, text) {
+                            Err(err) if is_not_found(&err) => None,
+                            result => Some(result),
+                        });
+                    copied.unwrap_or_else(|| Err(anyhow!("no command found")))?;
+                }
+            },
+        }
+        Ok(())
+    }
+}
+
+/// Runs a command that copies its input.
+fn run(program: &str, args: &[&str], text: &str) -> Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+
+    let status = child
+        .wait()
+        .with_context(|| format!("failed to run {program}"))?;
+    if !status.success() {
+        bail!("{program} exited with {status}");
+    }
+    Ok(())
+}
+
+fn is_not_found(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<io::Error>()
+        .is_some_and(|err| err.kind() == ErrorKind::NotFound)
+}
+
+/// The OSC 52 sequence that copies text. In tmux and screen it is wrapped
+/// such that they pass it on to the terminal.
+fn osc52(text: &str, term: &str, tmux: bool) -> String {
+    let sequence = format!("\x1b]52;c;{}\x07", STANDARD.encode(text));
+    if tmux {
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else if term.starts_with("screen") {
+        format!("\x1bP{sequence}\x1b\\")
+    } else {
+        sequence
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_osc52() {
+        assert_eq!(osc52("tar", "xterm", false), "\x1b]52;c;dGFy\x07");
+        assert_eq!(
+            osc52("tar", "tmux-256color", true),
+            "\x1bPtmux;\x1b\x1b]52;c;dGFy\x07\x1b\\"
+        );
+        assert_eq!(
+            osc52("tar", "screen", false),
+            "\x1bP\x1b]52;c;dGFy\x07\x1b\\"
+        );
+    }
+
+    #[test]
+    fn test_methods() {
+        assert_eq!(
+            ClipboardMethod::Auto.methods(true),
+            [Method::System, Method::Command, Method::Osc52]
+        );
+        assert_eq!(
+            ClipboardMethod::Auto.methods(false),
+            [Method::System, Method::Osc52, Method::Command]
+        );
+        assert_eq!(ClipboardMethod::Osc52.methods(true), [Method::Osc52]);
+    }
+
+    #[test]
+    fn test_command() {
+        let mut clipboard = Clipboard {
+            config: ClipboardConfig {
+                method: ClipboardMethod::Command,
+                command: Some(String::from("sh -c cat")),
+            },
+            system: None,
+        };
+        assert_eq!(
+            clipboard.copy("tar").unwrap(),
+            "Copied with a clipboard command"
+        );
+
+        clipboard.config.command = Some(String::from("false"));
+        assert!(clipboard.copy("tar").is_err());
+    }
+}
@@ -0,0 +1,241 @@
+use ratatui::style::{Color, Style};
+use serde::Deserialize;
+use std::{env, process::Command};
+
+/// The colors a terminal can display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorSupport {
+    /// 24-bit colors.
+    TrueColor,
+    /// The 256 color xterm palette.
+    Ansi256,
+    /// The 16 basic ANSI colors.
+    Ansi16,
+    /// No colors, only text attributes.
+    Monochrome,
+}
+
+/// The 16 ANSI colors with their default xterm values.
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// The levels of the 6x6x6 color cube of the 256 color palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl ColorSupport {
+    /// Detects the color support of the terminal from `NO_COLOR`,
+    /// `COLORTERM`, `TERM` and, as a last resort, terminfo.
+    pub fn detect() -> Self {
+        Self::from_env(
+            env::var("NO_COLOR").ok().as_deref(),
+            env::var("COLORTERM").ok().as_deref(),
+            env::var("TERM").ok().as_deref(),
+            terminfo_colors,
+        )
+    }
+
+    fn from_env(
+        no_color: Option<&str>,
+        colorterm: Option<&str>,
+        term: Option<&str>,
+        terminfo_colors: impl FnOnce() -> Option<u32>,
+    ) -> Self {
+        if no_color.is_some_and(|value| !value.is_empty()) {
+            return Self::Monochrome;
+        }
+
+        if matches!(colorterm, Some("truecolor" | "24bit")) {
+            return Self::TrueColor;
+        }
+
+        if let Some(term) = term {
+            if term == "dumb" {
+                return Self::Monochrome;
+            }
+            if term.ends_with("-direct") || term.contains("truecolor") {
+                return Self::TrueColor;
+            }
+            if term.ends_with("-256color") {
+                return Self::Ansi256;
+            }
+            if term == "linux" || term == "screen" || term.starts_with("vt") {
+                return Self::Ansi16;
+            }
+        }
+
+        match terminfo_colors() {
+            Some(colors) if colors >= 1 << 24 => Self::TrueColor,
+            Some(colors) if colors >= 256 => Self::Ansi256,
+            Some(colors) if colors >= 8 => Self::Ansi16,
+            Some(_) => Self::Monochrome,
+            None => Self::Ansi256,
+        }
+    }
+
+    /// Maps a color to the closest color the terminal can display.
+    pub fn map_color(self, color: Color) -> Option<Color> {
+        match self {
+            Self::TrueColor => Some(color),
+            Self::Ansi256 => match color {
+                Color::Rgb(r, g, b) => Some(Color::Indexed(to_ansi256((r, g, b)))),
+                color => Some(color),
+            },
+            Self::Ansi16 => match color {
+                Color::Rgb(r, g, b) => Some(to_ansi16((r, g, b))),
+                Color::Indexed(index) => Some(to_ansi16(ansi256_to_rgb(index))),
+                color => Some(color),
+            },
+            Self::Monochrome => None,
+        }
+    }
+
+    /// Maps the colors of a style to the closest colors the terminal can display.
+    pub fn map_style(self, style: Style) -> Style {
+        Style {
+            fg: style.fg.and_then(|color| self.map_color(color)),
+            bg: style.bg.and_then(|color| self.map_color(color)),
+            ..style
+        }
+    }
+}
+
+fn terminfo_colors() -> Option<u32> {
+    let output = Command::new("tput").arg("colors").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let dr = u32::from(r1.abs_diff(r2));
+    let dg = u32::from(g1.abs_diff(g2));
+    let db = u32::from(b1.abs_diff(b2));
+    dr * dr + dg * dg + db * db
+}
+
+fn to_ansi16(rgb: (u8, u8, u8)) -> Color {
+    ANSI16
+        .iter()
+        .min_by_key(|(_, ansi)| distance(rgb, *ansi))
+        .map(|(color, _)| *color)
+        .unwrap_or_default()
+}
+
+fn to_ansi256(rgb: (u8, u8, u8)) -> u8 {
+    let nearest_level = |value: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|i| CUBE_LEVELS[*i].abs_diff(value))
+            .unwrap_or_default() as u8
+    };
+
+    let (r, g, b) = (
+        nearest_level(rgb.0),
+        nearest_level(rgb.1),
+        nearest_level(rgb.2),
+    );
+    let cube = 16 + 36 * r + 6 * g + b;
+
+    // The gray ramp from 232 to 255 covers the values 8 to 238.
+    let average = (u16::from(rgb.0) + u16::from(rgb.1) + u16::from(rgb.2)) / 3;
+    let gray = 232 + (average.saturating_sub(3) / 10).min(23) as u8;
+
+    if distance(rgb, ansi256_to_rgb(gray)) < distance(rgb, ansi256_to_rgb(cube)) {
+        gray
+    } else {
+        cube
+    }
+}
+
+fn ansi256_to_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI16[index as usize].1,
+        16..=231 => {
+            let index = index - 16;
+            (
+                CUBE_LEVELS[(index / 36) as usize],
+                CUBE_LEVELS[(index / 6 % 6) as usize],
+                CUBE_LEVELS[(index % 6) as usize],
+            )
+        }
+        232..=255 => {
+            let value = 8 + 10 * (index - 232);
+            (value, value, value)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let detect = |no_color, colorterm, term, terminfo| {
+            ColorSupport::from_env(no_color, colorterm, term, || terminfo)
+        };
+
+        assert_eq!(
+            detect(Some("1"), Some("truecolor"), None, None),
+            ColorSupport::Monochrome
+        );
+        assert_eq!(
+            detect(Some(""), Some("truecolor"), None, None),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            detect(None, None, Some("tmux-256color"), None),
+            ColorSupport::Ansi256
+        );
+        assert_eq!(
+            detect(None, None, Some("linux"), None),
+            ColorSupport::Ansi16
+        );
+        assert_eq!(
+            detect(None, None, Some("xterm"), Some(8)),
+            ColorSupport::Ansi16
+        );
+        assert_eq!(
+            detect(None, None, Some("dumb"), None),
+            ColorSupport::Monochrome
+        );
+    }
+
+    #[test]
+    fn test_map_color() {
+        let orange = Color::Rgb(248, 154, 99);
+
+        assert_eq!(ColorSupport::TrueColor.map_color(orange), Some(orange));
+        assert_eq!(
+            ColorSupport::Ansi256.map_color(orange),
+            Some(Color::Indexed(209))
+        );
+        assert_eq!(
+            ColorSupport::Ansi256.map_color(Color::Rgb(16, 17, 22)),
+            Some(Color::Indexed(233))
+        );
+        assert_eq!(
+            ColorSupport::Ansi16.map_color(Color::Rgb(16, 17, 22)),
+            Some(Color::Black)
+        );
+        assert_eq!(ColorSupport::Monochrome.map_color(orange), None);
+    }
+}
@@ -1,6 +1,7 @@
 use super::app::AppState;
 use ratatui::crossterm::event::{self, Event as CrosstermEvent};
 use ratatui::crossterm::event::{KeyEvent, MouseEvent};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
 use std::time::{Duration, Instant};
 pub(crate) use tui_event_controller::EventfulWidget;
@@ -34,6 +35,26 @@ pub(crate) type EventContext<'a> = tui_event_controller::EventContext<'a, AppSta
 pub(crate) type IStatefulWidget<W> =
     tui_event_controller::InteractiveStatefulWidget<AppState, Event, W>;
 
+/// Set while another program, e.g. `$EDITOR`, uses the terminal.
+static PAUSED: AtomicBool = AtomicBool::new(false);
+/// Set by the event loop once it stopped reading events while paused.
+static IDLE: AtomicBool = AtomicBool::new(false);
+
+/// Stops reading terminal events, such that another program gets them,
+/// and waits until the event loop stopped.
+pub(crate) fn pause_events() {
+    PAUSED.store(true, Ordering::SeqCst);
+    let start = Instant::now();
+    while !IDLE.load(Ordering::SeqCst) && start.elapsed() < Duration::from_secs(1) {
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+/// Reads terminal events again after `pause_events`.
+pub(crate) fn resume_events() {
+    PAUSED.store(false, Ordering::SeqCst);
+}
+
 pub(crate) fn spawn_event_loop(controller: &EventController, tick_rate_ms: u64) {
     let tick_rate = Duration::from_millis(tick_rate_ms);
 
@@ -42,6 +63,13 @@ pub(crate) fn spawn_event_loop(controller: &EventController, tick_rate_ms: u64)
     thread::spawn(move || {
         let mut last_tick = Instant::now();
         loop {
+            if PAUSED.load(Ordering::SeqCst) {
+                IDLE.store(true, Ordering::SeqCst);
+                thread::sleep(Duration::from_millis(10));
+                continue;
+            }
+            IDLE.store(false, Ordering::SeqCst);
+
             // Emit crossterm events
             let timeout = tick_rate.saturating_sub(last_tick.elapsed());
             if event::poll(timeout).expect("unable to poll events") {
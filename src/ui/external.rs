@@ -0,0 +1,135 @@
+use anyhow::{bail, Context, Result};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::core::{read_text, Manual};
+
+/// A program that takes over the terminal, like `$EDITOR` or `$PAGER`.
+/// The app leaves the alternate screen while it runs.
+pub(crate) struct External {
+    program: String,
+    args: Vec<String>,
+    /// A temporary file that is removed once the program exits.
+    temporary: Option<PathBuf>,
+}
+
+impl External {
+    /// Opens a page source in `$EDITOR`. Sources of installed pages are opened
+    /// read-only, compressed ones as a decompressed copy.
+    pub(crate) fn editor(source: &Path, read_only: bool) -> Result<Self> {
+        let (program, mut args) = command("EDITOR", "vi")?;
+
+        let mut temporary = None;
+        let mut path = source.to_path_buf();
+        if source
+            .extension()
+            .is_some_and(|extension| extension == "gz")
+        {
+            let name = source.file_stem().unwrap_or_default().to_string_lossy();
+            let copy = env::temp_dir().join(format!("mantui-{name}"));
+            // A copy left over from a crash is read-only.
+            let _ = fs::remove_file(&copy);
+            fs::write(&copy, read_text(source)?)
+                .with_context(|| format!("failed to write {}", copy.display()))?;
+            set_read_only(&copy)?;
+            path = copy.clone();
+            temporary = Some(copy);
+        }
+
+        if read_only {
+            args.extend(read_only_args(&program).iter().map(ToString::to_string));
+        }
+        args.push(path.to_string_lossy().to_string());
+        Ok(Self {
+            program,
+            args,
+            temporary,
+        })
+    }
+
+    /// Opens the rendered text of a page in `$PAGER` at a line.
+    pub(crate) fn pager(manual: &Manual, name: &str, line: usize) -> Result<Self> {
+        let (program, mut args) = command("PAGER", "less")?;
+
+        let name: String = name
+            .chars()
+            .map(|ch| if ch.is_alphanumeric() { ch } else { '_' })
+            .collect();
+        let path = env::temp_dir().join(format!("mantui-{name}.txt"));
+        fs::write(&path, manual.overstrike())
+            .with_context(|| format!("failed to write {}", path.display()))?;
+
+        // `less`, `more` and `most` start at line `N` with `+N`.
+        args.push(format!("+{}", line + 1));
+        args.push(path.to_string_lossy().to_string());
+        Ok(Self {
+            program,
+            args,
+            temporary: Some(path),
+        })
+    }
+
+    /// Runs the program and waits for it to exit.
+    pub(crate) fn run(self) -> Result<()> {
+        let status = Command::new(&self.program).args(&self.args).status();
+        if let Some(temporary) = &self.temporary {
+            let _ = fs::remove_file(temporary);
+        }
+
+        let status = status.with_context(|| format!("failed to run {}", self.program))?;
+        if !status.success() {
+            bail!("{} exited with {status}", self.program);
+        }
+        Ok(())
+    }
+}
+
+/// Splits a command from an environment variable, e.g. `EDITOR="code --wait"`.
+fn command(variable: &str, default: &str) -> Result<(String, Vec<String>)> {
+    let value = env::var(variable)
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| default.to_string());
+
+    let mut parts = value.split_whitespace().map(str::to_string);
+    let Some(program) = parts.next() else {
+        bail!("${variable} is empty");
+    };
+    Ok((program, parts.collect()))
+}
+
+/// The arguments that open a file read-only in the editors that support it.
+fn read_only_args(program: &str) -> &'static [&'static str] {
+    let name = Path::new(program)
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy();
+    match name.as_ref() {
+        "vi" | "vim" | "nvim" | "gvim" | "view" => &["-R"],
+        "nano" => &["-v"],
+        "micro" => &["-readonly", "true"],
+        _ => &[],
+    }
+}
+
+fn set_read_only(path: &Path) -> Result<()> {
+    let mut permissions = fs::metadata(path)?.permissions();
+    permissions.set_readonly(true);
+    fs::set_permissions(path, permissions)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_read_only_args() {
+        assert_eq!(read_only_args("/usr/bin/nvim"), &["-R"]);
+        assert_eq!(read_only_args("nano"), &["-v"]);
+        assert!(read_only_args("code").is_empty());
+    }
+}
@@ -0,0 +1,446 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::fmt;
+
+/// Where a binding applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Context {
+    /// On every page.
+    Global,
+    Home,
+    List,
+    Reader,
+    /// While an info manual is shown in the reader.
+    Info,
+    Diff,
+    /// While typing a search query.
+    Search,
+}
+
+impl Context {
+    pub(crate) fn title(self) -> &'static str {
+        match self {
+            Context::Global => "Global",
+            Context::Home => "Home",
+            Context::List => "List",
+            Context::Reader => "Reader",
+            Context::Info => "Info manual",
+            Context::Diff => "Diff",
+            Context::Search => "Search input",
+        }
+    }
+}
+
+/// Groups bindings in the help.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Category {
+    General,
+    Navigation,
+    Tabs,
+    Split,
+    Search,
+    Mouse,
+}
+
+impl Category {
+    pub(crate) const ALL: [Category; 6] = [
+        Category::General,
+        Category::Navigation,
+        Category::Tabs,
+        Category::Split,
+        Category::Search,
+        Category::Mouse,
+    ];
+
+    pub(crate) fn title(self) -> &'static str {
+        match self {
+            Category::General => "General",
+            Category::Navigation => "Navigation",
+            Category::Tabs => "Tabs",
+            Category::Split => "Split view",
+            Category::Search => "Search",
+            Category::Mouse => "Mouse",
+        }
+    }
+}
+
+/// A key or mouse gesture that triggers a binding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Key {
+    Char(char),
+    Ctrl(char),
+    Code(KeyCode),
+    /// The digits `1` to `9`.
+    Digits,
+    /// A mouse gesture. Mouse events are handled by the widgets,
+    /// these are listed for the help only.
+    Mouse(&'static str),
+}
+
+impl Key {
+    fn matches(self, event: &KeyEvent) -> bool {
+        let ctrl = event.modifiers.contains(KeyModifiers::CONTROL);
+        match (self, event.code) {
+            (Key::Char(ch), KeyCode::Char(code)) => ch == code && !ctrl,
+            (Key::Ctrl(ch), KeyCode::Char(code)) => ch == code && ctrl,
+            (Key::Code(code), event_code) => code == event_code,
+            (Key::Digits, KeyCode::Char(code)) => matches!(code, '1'..='9') && !ctrl,
+            _ => false,
+        }
+    }
+
+    /// The key event of the key, if it is a single key. Used to run the
+    /// action of a binding from the command palette.
+    pub(crate) fn event(self) -> Option<KeyEvent> {
+        match self {
+            Key::Char(ch) => Some(KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE)),
+            Key::Ctrl(ch) => Some(KeyEvent::new(KeyCode::Char(ch), KeyModifiers::CONTROL)),
+            Key::Code(code) => Some(KeyEvent::new(code, KeyModifiers::NONE)),
+            Key::Digits | Key::Mouse(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Key::Char(ch) => write!(f, "{ch}"),
+            Key::Ctrl(ch) => write!(f, "Ctrl + {ch}"),
+            Key::Code(KeyCode::Enter) => write!(f, "Enter"),
+            Key::Code(KeyCode::Esc) => write!(f, "Esc"),
+            Key::Code(KeyCode::Backspace) => write!(f, "Backspace"),
+            Key::Code(KeyCode::Tab) => write!(f, "Tab"),
+            Key::Code(KeyCode::BackTab) => write!(f, "Shift + Tab"),
+            Key::Code(KeyCode::Up) => write!(f, "↑"),
+            Key::Code(KeyCode::Down) => write!(f, "↓"),
+            Key::Code(KeyCode::Left) => write!(f, "←"),
+            Key::Code(KeyCode::Right) => write!(f, "→"),
+            Key::Code(code) => write!(f, "{code:?}"),
+            Key::Digits => write!(f, "1-9"),
+            Key::Mouse(gesture) => write!(f, "{gesture}"),
+        }
+    }
+}
+
+/// What a binding does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Action {
+    /// Mouse bindings, which are handled by the widgets.
+    None,
+    Quit,
+    Help,
+    CommandPalette,
+    ChangeTheme,
+    Continue,
+    ScrollDown,
+    ScrollUp,
+    HalfPageDown,
+    HalfPageUp,
+    Top,
+    Bottom,
+    SetMark,
+    JumpToMark,
+    Marks,
+    SelectSection(usize),
+    Open,
+    Back,
+    Search,
+    NextMatch,
+    PreviousMatch,
+    ConfirmSearch,
+    DeleteChar,
+    Star,
+    Collections,
+    RemoveFromCollection,
+    Recent,
+    Annotate,
+    OpenInNewTab,
+    NextTab,
+    PreviousTab,
+    CloseTab,
+    SplitView,
+    FocusPane,
+    SyncScroll,
+    Diff,
+    NextHunk,
+    PreviousHunk,
+    ToggleDiffLayout,
+    Export,
+    Diagnostics,
+    Tldr,
+    InfoManual,
+    Edit,
+    Pager,
+    CopySection,
+    CopyExample,
+    Examples,
+    NextNode,
+    PreviousNode,
+    UpNode,
+    BackNode,
+    NextReference,
+    PreviousReference,
+    InfoIndex,
+}
+
+/// A key binding.
+pub(crate) struct Binding {
+    pub(crate) keys: &'static [Key],
+    pub(crate) action: Action,
+    pub(crate) description: &'static str,
+    pub(crate) category: Category,
+    pub(crate) context: Context,
+}
+
+macro_rules! bind {
+    ($context:ident, $category:ident, [$($key:expr),+], $action:expr, $description:literal) => {
+        Binding {
+            keys: &[$($key),+],
+            action: $action,
+            description: $description,
+            category: Category::$category,
+            context: Context::$context,
+        }
+    };
+}
+
+/// All key bindings. The key handlers, the help popup and the README are based on this table.
+#[rustfmt::skip]
+pub(crate) const BINDINGS: &[Binding] = &[
+    // Global
+    bind!(Global, General, [Key::Char('?')], Action::Help, "Show key bindings"),
+    bind!(Global, General, [Key::Char(':')], Action::CommandPalette, "Open the command palette"),
+    bind!(Global, General, [Key::Char('T')], Action::ChangeTheme, "Change theme"),
+    bind!(Global, General, [Key::Ctrl('c')], Action::Quit, "Quit"),
+    bind!(Global, Tabs, [Key::Code(KeyCode::Tab)], Action::NextTab, "Next tab"),
+    bind!(Global, Tabs, [Key::Code(KeyCode::BackTab)], Action::PreviousTab, "Previous tab"),
+    bind!(Global, Tabs, [Key::Ctrl('w')], Action::CloseTab, "Close tab"),
+    // Home
+    bind!(Home, General, [Key::Code(KeyCode::Enter), Key::Mouse("Mouse click")], Action::Continue, "Continue"),
+    bind!(Home, General, [Key::Char('r')], Action::Recent, "Show recently read pages"),
+    // List
+    bind!(List, Navigation, [Key::Char('j'), Key::Code(KeyCode::Down)], Action::ScrollDown, "Next command"),
+    bind!(List, Navigation, [Key::Char('k'), Key::Code(KeyCode::Up)], Action::ScrollUp, "Previous command"),
+    bind!(List, Navigation, [Key::Ctrl('d')], Action::HalfPageDown, "Half page down"),
+    bind!(List, Navigation, [Key::Ctrl('u')], Action::HalfPageUp, "Half page up"),
+    bind!(List, Navigation, [Key::Digits], Action::SelectSection(0), "Select section"),
+    bind!(List, General, [Key::Code(KeyCode::Enter)], Action::Open, "Open man page"),
+    bind!(List, Tabs, [Key::Char('t')], Action::OpenInNewTab, "Open man page in a new tab"),
+    bind!(List, General, [Key::Char('r')], Action::Recent, "Show recently read pages"),
+    bind!(List, General, [Key::Char('s')], Action::Star, "Add to or remove from favorites"),
+    bind!(List, General, [Key::Char('c')], Action::Collections, "Manage collections"),
+    bind!(List, General, [Key::Char('x')], Action::RemoveFromCollection, "Remove from the shown collection"),
+    bind!(List, Search, [Key::Char('/')], Action::Search, "Search commands"),
+    bind!(List, Search, [Key::Code(KeyCode::Esc)], Action::Back, "Clear search"),
+    bind!(List, Mouse, [Key::Mouse("Mouse click")], Action::None, "Select, click again to open"),
+    bind!(List, Mouse, [Key::Mouse("Mouse wheel")], Action::None, "Scroll commands"),
+    // Reader
+    bind!(Reader, Navigation, [Key::Char('j'), Key::Code(KeyCode::Down)], Action::ScrollDown, "Scroll down"),
+    bind!(Reader, Navigation, [Key::Char('k'), Key::Code(KeyCode::Up)], Action::ScrollUp, "Scroll up"),
+    bind!(Reader, Navigation, [Key::Ctrl('d')], Action::HalfPageDown, "Half page down"),
+    bind!(Reader, Navigation, [Key::Ctrl('u')], Action::HalfPageUp, "Half page up"),
+    bind!(Reader, Navigation, [Key::Char('g')], Action::Top, "Go to top"),
+    bind!(Reader, Navigation, [Key::Char('G')], Action::Bottom, "Go to bottom"),
+    bind!(Reader, Navigation, [Key::Char('m')], Action::SetMark, "Set a mark at the top line, followed by a letter"),
+    bind!(Reader, Navigation, [Key::Char('\'')], Action::JumpToMark, "Jump to a mark, followed by its letter ('' goes back)"),
+    bind!(Reader, Navigation, [Key::Char('M')], Action::Marks, "List the marks"),
+    bind!(Reader, General, [Key::Code(KeyCode::Esc)], Action::Back, "Clear selection or search, close split, go back"),
+    bind!(Reader, General, [Key::Char('s')], Action::Star, "Add to or remove from favorites"),
+    bind!(Reader, General, [Key::Char('c')], Action::Collections, "Manage collections"),
+    bind!(Reader, General, [Key::Char('a')], Action::Annotate, "Add or edit a note on the clicked or top line"),
+    bind!(Reader, Split, [Key::Char('v')], Action::SplitView, "Open a page side by side"),
+    bind!(Reader, Split, [Key::Char('w')], Action::FocusPane, "Move focus to the other pane"),
+    bind!(Reader, Split, [Key::Char('S')], Action::SyncScroll, "Toggle synchronized scrolling"),
+    bind!(Reader, General, [Key::Char('D')], Action::Diff, "Compare with another page or file"),
+    bind!(Reader, General, [Key::Char('e')], Action::Export, "Export to Markdown, HTML or text"),
+    bind!(Reader, General, [Key::Char('t')], Action::Tldr, "Switch between the man page and its tldr page"),
+    bind!(Reader, General, [Key::Char('I')], Action::InfoManual, "View the info manual of the page"),
+    bind!(Reader, General, [Key::Char('E')], Action::Edit, "Open the source in $EDITOR"),
+    bind!(Reader, General, [Key::Char('P')], Action::Pager, "Open the text in $PAGER at the clicked or top line"),
+    bind!(Reader, General, [Key::Char('Y')], Action::CopySection, "Copy the section at the clicked or top line"),
+    bind!(Reader, General, [Key::Char('y')], Action::CopyExample, "Copy the example at or below the clicked or top line"),
+    bind!(Reader, General, [Key::Char('x')], Action::Examples, "List the examples to copy one"),
+    bind!(Reader, General, [Key::Char('!')], Action::Diagnostics, "Show or hide the warnings of a page file"),
+    bind!(Reader, Search, [Key::Char('/')], Action::Search, "Search"),
+    bind!(Reader, Search, [Key::Char('n')], Action::NextMatch, "Next match"),
+    bind!(Reader, Search, [Key::Char('N')], Action::PreviousMatch, "Previous match"),
+    bind!(Reader, Mouse, [Key::Mouse("Mouse drag")], Action::None, "Select and copy text"),
+    bind!(Reader, Mouse, [Key::Mouse("Double click")], Action::None, "Select and copy a word, flag, path or page reference"),
+    bind!(Reader, Mouse, [Key::Mouse("Triple click")], Action::None, "Select and copy a line"),
+    bind!(Reader, Mouse, [Key::Mouse("Shift + click")], Action::None, "Extend the selection"),
+    bind!(Reader, Mouse, [Key::Mouse("Mouse wheel")], Action::None, "Scroll"),
+    bind!(Reader, Mouse, [Key::Mouse("Mouse click on note marker")], Action::None, "Show note"),
+    // Info manual
+    bind!(Info, Navigation, [Key::Char('>')], Action::NextNode, "Next node"),
+    bind!(Info, Navigation, [Key::Char('<')], Action::PreviousNode, "Previous node"),
+    bind!(Info, Navigation, [Key::Char('u')], Action::UpNode, "Up to the parent node"),
+    bind!(Info, Navigation, [Key::Code(KeyCode::Backspace)], Action::BackNode, "Back to the last visited node"),
+    bind!(Info, Navigation, [Key::Char(']')], Action::NextReference, "Select the next menu entry or cross-reference"),
+    bind!(Info, Navigation, [Key::Char('[')], Action::PreviousReference, "Select the previous menu entry or cross-reference"),
+    bind!(Info, Navigation, [Key::Code(KeyCode::Enter)], Action::Open, "Follow the selected reference"),
+    bind!(Info, Search, [Key::Char('i')], Action::InfoIndex, "Search the index"),
+    bind!(Info, Mouse, [Key::Mouse("Mouse click on a reference")], Action::None, "Follow the reference"),
+    // Diff
+    bind!(Diff, Navigation, [Key::Char('j'), Key::Code(KeyCode::Down)], Action::ScrollDown, "Scroll down"),
+    bind!(Diff, Navigation, [Key::Char('k'), Key::Code(KeyCode::Up)], Action::ScrollUp, "Scroll up"),
+    bind!(Diff, Navigation, [Key::Ctrl('d')], Action::HalfPageDown, "Half page down"),
+    bind!(Diff, Navigation, [Key::Ctrl('u')], Action::HalfPageUp, "Half page up"),
+    bind!(Diff, Navigation, [Key::Char('g')], Action::Top, "Go to top"),
+    bind!(Diff, Navigation, [Key::Char('G')], Action::Bottom, "Go to bottom"),
+    bind!(Diff, Navigation, [Key::Char('n'), Key::Char(']')], Action::NextHunk, "Next change"),
+    bind!(Diff, Navigation, [Key::Char('N'), Key::Char('[')], Action::PreviousHunk, "Previous change"),
+    bind!(Diff, General, [Key::Char('u')], Action::ToggleDiffLayout, "Switch between unified and side by side"),
+    bind!(Diff, General, [Key::Code(KeyCode::Esc)], Action::Back, "Go back"),
+    bind!(Diff, Mouse, [Key::Mouse("Mouse wheel")], Action::None, "Scroll"),
+    // Search input
+    bind!(Search, Search, [Key::Code(KeyCode::Enter), Key::Code(KeyCode::Esc)], Action::ConfirmSearch, "Finish typing"),
+    bind!(Search, Search, [Key::Code(KeyCode::Backspace)], Action::DeleteChar, "Delete character"),
+];
+
+/// Returns the action bound to a key event, looking up the bindings of the
+/// context before the global bindings. While typing a search query, only the
+/// bindings of the search input apply.
+pub(crate) fn action(context: Context, event: &KeyEvent) -> Option<Action> {
+    let contexts: &[Context] = match context {
+        Context::Global | Context::Search => &[context],
+        _ => &[context, Context::Global],
+    };
+
+    let binding = contexts.iter().find_map(|context| {
+        BINDINGS
+            .iter()
+            .filter(|binding| binding.context == *context)
+            .find(|binding| binding.keys.iter().any(|key| key.matches(event)))
+    })?;
+
+    match (binding.action, event.code) {
+        (Action::SelectSection(_), KeyCode::Char(ch)) => {
+            Some(Action::SelectSection(ch as usize - '1' as usize))
+        }
+        (action, _) => Some(action),
+    }
+}
+
+/// Returns the contexts whose bindings apply on a page, e.g. the reader
+/// and the search input while an info manual is shown.
+pub(crate) fn contexts(context: Context) -> Vec<Context> {
+    let mut contexts = vec![context];
+    if context == Context::Info {
+        contexts.push(Context::Reader);
+    }
+    if matches!(context, Context::List | Context::Reader | Context::Info) {
+        contexts.push(Context::Search);
+    }
+    if context != Context::Global {
+        contexts.push(Context::Global);
+    }
+    contexts
+}
+
+/// Returns the bindings that apply in a context, grouped by category.
+pub(crate) fn bindings_by_category(contexts: &[Context]) -> Vec<(Category, Vec<&'static Binding>)> {
+    Category::ALL
+        .into_iter()
+        .map(|category| {
+            let bindings = BINDINGS
+                .iter()
+                .filter(|binding| binding.category == category)
+                .filter(|binding| contexts.contains(&binding.context))
+                .collect();
+            (category, bindings)
+        })
+        .filter(|(_, bindings): &(_, Vec<_>)| !bindings.is_empty())
+        .collect()
+}
+
+/// Formats the keys of a binding, e.g. `j / ↓`.
+pub(crate) fn format_keys(binding: &Binding) -> String {
+    binding
+        .keys
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" / ")
+}
+
+/// Renders the bindings as markdown tables, one per context.
+#[cfg(test)]
+fn markdown() -> String {
+    let contexts = [
+        Context::Global,
+        Context::Home,
+        Context::List,
+        Context::Reader,
+        Context::Info,
+        Context::Diff,
+        Context::Search,
+    ];
+
+    let mut markdown = String::new();
+    for context in contexts {
+        markdown.push_str(&format!("### {}\n\n", context.title()));
+        markdown.push_str("| Action | Keys |\n");
+        markdown.push_str("| ------ | ---- |\n");
+        for (_, bindings) in bindings_by_category(&[context]) {
+            for binding in bindings {
+                let keys = binding
+                    .keys
+                    .iter()
+                    .map(|key| format!("`{key}`"))
+                    .collect::<Vec<_>>()
+                    .join(" / ");
+                markdown.push_str(&format!("| {} | {keys} |\n", binding.description));
+            }
+        }
+        markdown.push('\n');
+    }
+    markdown
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_action() {
+        let none = KeyModifiers::NONE;
+
+        assert_eq!(
+            action(Context::Reader, &key(KeyCode::Char('j'), none)),
+            Some(Action::ScrollDown)
+        );
+        assert_eq!(
+            action(
+                Context::Reader,
+                &key(KeyCode::Char('d'), KeyModifiers::CONTROL)
+            ),
+            Some(Action::HalfPageDown)
+        );
+        assert_eq!(
+            action(Context::Reader, &key(KeyCode::Char('d'), none)),
+            None
+        );
+        assert_eq!(
+            action(Context::List, &key(KeyCode::Char('3'), none)),
+            Some(Action::SelectSection(2))
+        );
+        assert_eq!(
+            action(Context::Home, &key(KeyCode::Char('?'), none)),
+            Some(Action::Help)
+        );
+    }
+
+    #[test]
+    fn test_readme_is_up_to_date() {
+        let readme = include_str!("../../README.md");
+        let markdown = markdown();
+
+        assert!(
+            readme.contains(&markdown),
+            "the key bindings in the README are outdated, expected:\n\n{markdown}"
+        );
+    }
+}
@@ -1,9 +1,23 @@
 mod app;
+mod clipboard;
+mod colors;
 pub mod debug;
 mod events;
+mod external;
+mod keymap;
 mod pages;
+mod popups;
+mod print;
+mod status;
+mod tabs;
 mod terminal;
 pub mod theme;
-pub use theme::{Theme, THEME};
+pub(crate) use clipboard::{set_clipboard, ClipboardConfig};
+pub use colors::ColorSupport;
+pub(crate) use print::print;
+pub(crate) use status::{set_status_fields, StatusField};
+pub use theme::{set_theme, ThemeSelection};
 
 pub use app::App;
+pub(crate) use app::Navigation;
+pub(crate) use terminal::read_stdin;
@@ -0,0 +1,325 @@
+#![allow(clippy::cast_possible_truncation)]
+#![allow(clippy::cast_sign_loss, clippy::cast_precision_loss)]
+use crate::core::{hunks, Change, Diff, DiffLine, Source};
+use crate::ui::app::{ActiveState, AppState, Navigation};
+use crate::ui::events::{Event, EventContext, EventfulWidget};
+use crate::ui::keymap::{self, Action, Context};
+use crate::ui::popups;
+use crate::ui::theme::get_theme;
+use ratatui::buffer::Buffer;
+use ratatui::crossterm::event::MouseEventKind;
+use ratatui::prelude::*;
+use ratatui::widgets::{
+    Block, BorderType, Borders, Padding, Paragraph, Scrollbar, ScrollbarOrientation,
+    ScrollbarState, StatefulWidgetRef,
+};
+use std::cmp::min;
+
+/// The terminal width from which diffs are shown side by side.
+const SIDE_BY_SIDE_WIDTH: usize = 120;
+
+/// The number of rows shown above a change after jumping to it.
+const CONTEXT_ROWS: usize = 3;
+
+pub(crate) struct DiffPage;
+
+/// Compares the rendered text of two man pages.
+pub(crate) struct DiffPageState {
+    old: Source,
+    new: Source,
+    diff: Diff,
+    error: Option<String>,
+    side_by_side: bool,
+    /// The lines shown in each row. In the unified view only the left one is set.
+    rows: Vec<(Option<usize>, Option<usize>)>,
+    /// The rows at which the changes start.
+    hunks: Vec<usize>,
+    scroll_offset: usize,
+    page_height: usize,
+    max_scroll_pos: usize,
+    scrollbar: ScrollbarState,
+}
+
+impl DiffPageState {
+    pub(crate) fn new(old: Source, new: Source, width: usize) -> Self {
+        let mut state = Self {
+            old,
+            new,
+            diff: Diff::default(),
+            error: None,
+            side_by_side: width >= SIDE_BY_SIDE_WIDTH,
+            rows: Vec::new(),
+            hunks: Vec::new(),
+            scroll_offset: 0,
+            page_height: 0,
+            max_scroll_pos: 0,
+            scrollbar: ScrollbarState::default(),
+        };
+        state.load(width);
+        state
+    }
+
+    pub(crate) fn title(&self) -> String {
+        format!("{} ↔ {}", self.old, self.new)
+    }
+
+    /// Renders both pages for the current layout and compares them.
+    fn load(&mut self, width: usize) {
+        let width = if self.side_by_side { width / 2 } else { width };
+        let width = format!("{}", (width as f64 * 0.9) as u16);
+
+        let read = |source: &Source| {
+            source
+                .read(&width)
+                .map(|manual| manual.plain_lines())
+                .map_err(|err| format!("failed to read {source}: {err}"))
+        };
+        match (read(&self.old), read(&self.new)) {
+            (Ok(old), Ok(new)) => {
+                self.diff = Diff::new(&old, &new);
+                self.error = None;
+            }
+            (Err(err), _) | (_, Err(err)) => {
+                self.diff = Diff::default();
+                self.error = Some(err);
+            }
+        }
+
+        self.rows = if self.side_by_side {
+            self.diff.side_by_side()
+        } else {
+            (0..self.diff.lines.len())
+                .map(|i| (Some(i), None))
+                .collect()
+        };
+        self.hunks = hunks(self.rows.iter().map(|row| self.is_changed(*row)));
+    }
+
+    fn is_changed(&self, (left, right): (Option<usize>, Option<usize>)) -> bool {
+        [left, right]
+            .into_iter()
+            .flatten()
+            .any(|line| self.diff.lines[line].change != Change::Equal)
+    }
+
+    /// Switches between the unified and the side by side view.
+    fn toggle_layout(&mut self, width: usize) {
+        let (old, new) = self
+            .rows
+            .get(self.scroll_offset)
+            .copied()
+            .unwrap_or_default();
+
+        self.side_by_side = !self.side_by_side;
+        self.load(width);
+
+        // Keep the line at the top in view.
+        if let Some(line) = old.or(new) {
+            self.scroll_offset = self
+                .rows
+                .iter()
+                .position(|(old, new)| *old == Some(line) || *new == Some(line))
+                .unwrap_or_default();
+        }
+    }
+
+    /// Scrolls to the next change. Returns `false` if there is none.
+    fn next_hunk(&mut self) -> bool {
+        let current = self.scroll_offset + CONTEXT_ROWS;
+        let next = self.hunks.iter().find(|row| **row > current);
+        match next.filter(|_| self.scroll_offset < self.max_scroll_pos) {
+            Some(row) => {
+                self.scroll_offset = row.saturating_sub(CONTEXT_ROWS);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Scrolls to the previous change. Returns `false` if there is none.
+    fn previous_hunk(&mut self) -> bool {
+        let current = min(self.scroll_offset, self.max_scroll_pos) + CONTEXT_ROWS;
+        match self.hunks.iter().rev().find(|row| **row < current) {
+            Some(row) => {
+                self.scroll_offset = row.saturating_sub(CONTEXT_ROWS);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the number of the change at the top, counting from 1.
+    fn current_hunk(&self) -> usize {
+        let current = self.scroll_offset + CONTEXT_ROWS;
+        self.hunks.iter().filter(|row| **row <= current).count()
+    }
+
+    fn scroll_up(&mut self) {
+        self.scroll_offset = self.scroll_offset.saturating_sub(1);
+    }
+
+    fn scroll_down(&mut self) {
+        self.scroll_offset = min(self.scroll_offset + 1, self.max_scroll_pos);
+    }
+}
+
+impl EventfulWidget<AppState, Event> for DiffPage {
+    fn unique_key() -> String {
+        String::from("DiffPage")
+    }
+
+    fn on_event(ctx: EventContext, app_state: &mut AppState, area: Option<Rect>) {
+        if !matches!(app_state.active_state, ActiveState::Diff(_)) {
+            return;
+        }
+
+        if popups::on_event(&ctx, app_state) {
+            return;
+        }
+
+        let width = app_state.width();
+        let ActiveState::Diff(state) = &mut app_state.active_state else {
+            return;
+        };
+
+        if let Event::Mouse(event) = ctx.event {
+            let position = Position::new(event.column, event.row);
+            if !area.is_some_and(|area| area.contains(position)) {
+                return;
+            }
+            match event.kind {
+                MouseEventKind::ScrollUp => state.scroll_up(),
+                MouseEventKind::ScrollDown => state.scroll_down(),
+                _ => {}
+            }
+            return;
+        }
+
+        let Event::Key(key) = ctx.event else {
+            return;
+        };
+
+        match keymap::action(Context::Diff, key) {
+            Some(Action::ScrollDown) => state.scroll_down(),
+            Some(Action::ScrollUp) => state.scroll_up(),
+            Some(Action::HalfPageDown) => {
+                state.scroll_offset = min(
+                    state.scroll_offset + state.page_height / 2,
+                    state.max_scroll_pos,
+                );
+            }
+            Some(Action::HalfPageUp) => {
+                state.scroll_offset = state.scroll_offset.saturating_sub(state.page_height / 2);
+            }
+            Some(Action::Top) => state.scroll_offset = 0,
+            Some(Action::Bottom) => state.scroll_offset = state.max_scroll_pos,
+            Some(Action::NextHunk) => {
+                let found = state.next_hunk();
+                if !found {
+                    app_state.notify("No more changes");
+                }
+            }
+            Some(Action::PreviousHunk) => {
+                let found = state.previous_hunk();
+                if !found {
+                    app_state.notify("No previous changes");
+                }
+            }
+            Some(Action::ToggleDiffLayout) => state.toggle_layout(width),
+            Some(Action::Back) => {
+                Navigation::navigate_to(Navigation::List, app_state, ctx.controller);
+            }
+            Some(action) => popups::open(action, Context::Diff, app_state),
+            None => {}
+        }
+    }
+}
+
+impl StatefulWidgetRef for DiffPage {
+    type State = DiffPageState;
+
+    fn render_ref(&self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let theme = get_theme();
+        buf.set_style(area, theme.base);
+
+        let status = if state.error.is_some() {
+            String::new()
+        } else if state.diff.is_empty() {
+            String::from(" No differences ")
+        } else {
+            let (current, total) = (state.current_hunk(), state.hunks.len());
+            format!(" Change {current} of {total} ")
+        };
+        let block = Block::default()
+            .style(theme.block.active)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" {} ", state.title()))
+            .title_top(Line::from(status).right_aligned())
+            .padding(Padding::horizontal(1));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        if let Some(error) = &state.error {
+            Paragraph::new(error.as_str()).render(inner, buf);
+            return;
+        }
+
+        state.page_height = inner.height as usize;
+        state.max_scroll_pos = state.rows.len().saturating_sub(state.page_height);
+        state.scroll_offset = state.scroll_offset.min(state.max_scroll_pos);
+
+        let rows = &state.rows[state.scroll_offset..];
+        let rows = &rows[..min(rows.len(), state.page_height)];
+        let line = |index: Option<usize>| {
+            index.map_or_else(Line::default, |index| diff_line(&state.diff.lines[index]))
+        };
+
+        if state.side_by_side {
+            let [left, separator, right] = Layout::horizontal([
+                Constraint::Fill(1),
+                Constraint::Length(1),
+                Constraint::Fill(1),
+            ])
+            .areas(inner);
+
+            let lines: Vec<Line> = rows.iter().map(|(old, _)| line(*old)).collect();
+            Paragraph::new(lines).render(left, buf);
+            let lines: Vec<Line> = rows.iter().map(|(_, new)| line(*new)).collect();
+            Paragraph::new(lines).render(right, buf);
+            for y in separator.top()..separator.bottom() {
+                if let Some(cell) = buf.cell_mut((separator.x, y)) {
+                    cell.set_symbol("│").set_style(theme.block.inactive);
+                }
+            }
+        } else {
+            let lines: Vec<Line> = rows.iter().map(|(old, _)| line(*old)).collect();
+            Paragraph::new(lines).render(inner, buf);
+        }
+
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(Some("╮"))
+            .end_symbol(Some("╯"));
+        state.scrollbar = state
+            .scrollbar
+            .content_length(state.max_scroll_pos)
+            .position(state.scroll_offset);
+        scrollbar.render(area, buf, &mut state.scrollbar);
+    }
+}
+
+/// Styles a line of a diff with a `-` or `+` sign and highlighted changed words.
+fn diff_line(line: &DiffLine) -> Line<'_> {
+    let theme = get_theme();
+    let (sign, style, changed) = match line.change {
+        Change::Equal => (" ", theme.base, theme.base),
+        Change::Removed => ("-", theme.diff.removed, theme.diff.removed_word),
+        Change::Added => ("+", theme.diff.added, theme.diff.added_word),
+    };
+
+    let mut spans = vec![Span::styled(format!("{sign} "), style)];
+    spans.extend(line.runs.iter().map(|(is_changed, text)| {
+        Span::styled(text.as_str(), if *is_changed { changed } else { style })
+    }));
+    Line::from(spans)
+}
@@ -1,5 +0,0 @@
-#[allow(unused)]
-struct Tag {
-    key: String,
-    description: String,
-}
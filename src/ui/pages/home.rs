@@ -2,10 +2,12 @@ use super::{utils::centered_rect, ListPage, ListPageState};
 use crate::ui::{
     app::{ActiveState, ActiveWidget, AppState},
     events::{Event, EventContext, EventController, EventfulWidget, IStatefulWidget},
+    keymap::{self, Action, Context},
+    popups,
     theme::get_theme,
 };
 use ratatui::{
-    crossterm::event::{KeyCode, MouseEventKind},
+    crossterm::event::MouseEventKind,
     prelude::*,
     widgets::{Paragraph, StatefulWidgetRef},
 };
@@ -26,9 +28,19 @@ impl EventfulWidget<AppState, Event> for HomePage {
     }
 
     fn on_event(ctx: EventContext, state: &mut AppState, _: Option<Rect>) {
+        if !matches!(state.active_state, ActiveState::Home(_)) {
+            return;
+        }
+
+        if popups::on_event(&ctx, state) {
+            return;
+        }
+
         if let Event::Key(key) = ctx.event {
-            if key.code == KeyCode::Enter {
-                next_page(ctx.controller, state);
+            match keymap::action(Context::Home, key) {
+                Some(Action::Continue) => next_page(ctx.controller, state),
+                Some(action) => popups::open(action, Context::Home, state),
+                None => {}
             }
         }
         if let Event::Mouse(event) = ctx.event {
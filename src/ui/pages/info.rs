@@ -0,0 +1,159 @@
+use anyhow::{anyhow, Result};
+
+use crate::core::{IndexEntry, InfoManual, Link, Manual, Reference};
+
+/// An info manual in the reader: the displayed node, its references and the
+/// visited nodes to go back to.
+pub(crate) struct InfoView {
+    manual: InfoManual,
+    node: String,
+    references: Vec<Reference>,
+    selected: Option<usize>,
+    /// The visited manuals and nodes with their scroll offsets.
+    history: Vec<(String, String, usize)>,
+}
+
+impl InfoView {
+    /// Opens the info manual of a program at its `Top` node, or at the node
+    /// its entry in the `dir` file points to.
+    pub(crate) fn open(name: &str) -> Result<Self> {
+        let manual = InfoManual::load(name)?;
+        let node = manual
+            .start()
+            .map(|node| node.name.clone())
+            .ok_or_else(|| anyhow!("the info manual of {name} has no nodes"))?;
+
+        let mut view = Self {
+            manual,
+            node,
+            references: Vec::new(),
+            selected: None,
+            history: Vec::new(),
+        };
+        view.update_references()?;
+        Ok(view)
+    }
+
+    /// Returns the displayed node with its references underlined.
+    pub(crate) fn render(&self) -> Manual {
+        self.manual
+            .node(&self.node)
+            .map(|node| node.to_manual(&self.references))
+            .unwrap_or_default()
+    }
+
+    /// The name of the manual, e.g. `tar`.
+    pub(crate) fn name(&self) -> &str {
+        &self.manual.name
+    }
+
+    /// The manual and the displayed node, e.g. `tar (info) › Introduction`.
+    pub(crate) fn title(&self) -> String {
+        format!("{} (info) › {}", self.manual.name, self.node)
+    }
+
+    /// Goes to a node, in another manual if `file` is set, and remembers
+    /// the displayed node to go back to.
+    pub(crate) fn go(&mut self, file: Option<&str>, node: &str, offset: usize) -> Result<()> {
+        let previous = (self.manual.name.clone(), self.node.clone(), offset);
+        let manual = match file {
+            Some(file) if !file.eq_ignore_ascii_case(&self.manual.name) => {
+                Some(InfoManual::load(file)?)
+            }
+            _ => None,
+        };
+
+        let target = manual.as_ref().unwrap_or(&self.manual);
+        let node = target
+            .node(node)
+            .map(|node| node.name.clone())
+            .ok_or_else(|| anyhow!("no node named {node}"))?;
+
+        if let Some(manual) = manual {
+            self.manual = manual;
+        }
+        self.node = node;
+        self.history.push(previous);
+        self.update_references()
+    }
+
+    /// Goes to the next, previous or parent node.
+    pub(crate) fn go_to_link(&mut self, link: Link, offset: usize) -> Result<()> {
+        let (file, node) = self
+            .manual
+            .node(&self.node)
+            .and_then(|node| node.link(link))
+            .ok_or_else(|| anyhow!("{} has no {link:?} node", self.node))?;
+        self.go(file.as_deref(), &node, offset)
+    }
+
+    /// Goes back to the previously visited node and returns its scroll offset.
+    pub(crate) fn back(&mut self) -> Option<usize> {
+        let (name, node, offset) = self.history.pop()?;
+        if name != self.manual.name {
+            self.manual = InfoManual::load(&name).ok()?;
+        }
+        self.node = node;
+        self.update_references().ok()?;
+        Some(offset)
+    }
+
+    /// Selects the next or previous reference and returns its line.
+    pub(crate) fn select_reference(&mut self, forward: bool, first_line: usize) -> Option<usize> {
+        if self.references.is_empty() {
+            return None;
+        }
+
+        let last = self.references.len() - 1;
+        let selected = match (self.selected, forward) {
+            (Some(selected), true) => (selected + 1).min(last),
+            (Some(selected), false) => selected.saturating_sub(1),
+            // Start at the first visible reference.
+            (None, true) => self
+                .references
+                .iter()
+                .position(|reference| reference.line >= first_line)
+                .unwrap_or(last),
+            (None, false) => self
+                .references
+                .iter()
+                .rposition(|reference| reference.line < first_line)
+                .unwrap_or(0),
+        };
+        self.selected = Some(selected);
+        Some(self.references[selected].line)
+    }
+
+    /// The selected reference, if any.
+    pub(crate) fn selected(&self) -> Option<&Reference> {
+        self.selected.and_then(|index| self.references.get(index))
+    }
+
+    /// Returns the reference at a line and column.
+    pub(crate) fn reference_at(&self, line: usize, column: usize) -> Option<&Reference> {
+        self.references
+            .iter()
+            .find(|reference| reference.line == line && reference.columns.contains(&column))
+    }
+
+    /// Follows a reference of the displayed node.
+    pub(crate) fn follow(&mut self, reference: &Reference, offset: usize) -> Result<()> {
+        self.go(reference.file.as_deref(), &reference.node, offset)
+    }
+
+    /// Returns the entries of the index of the manual.
+    pub(crate) fn index(&self) -> Vec<IndexEntry> {
+        self.manual.index()
+    }
+
+    /// Finds the references of the displayed node, none is selected.
+    fn update_references(&mut self) -> Result<()> {
+        let node = self
+            .manual
+            .node(&self.node)
+            .ok_or_else(|| anyhow!("no node named {}", self.node))?;
+        self.references = node.references();
+        self.selected = None;
+        Ok(())
+    }
+}
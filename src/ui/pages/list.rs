@@ -1,28 +1,44 @@
-use std::cmp::min;
+use std::{
+    cmp::min,
+    collections::HashSet,
+    sync::{Arc, OnceLock},
+};
 
-use ratatui::crossterm::event::{KeyCode, KeyModifiers, MouseEventKind};
+use ratatui::crossterm::event::{KeyCode, MouseEventKind};
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, StatefulWidgetRef, Widget};
 use tachyonfx::CenteredShrink;
 use throbber_widgets_tui::{Throbber, ThrobberState};
 use tui_widget_list::{ListBuilder, ListState, ListView};
 
+use crate::core::{Collections, PageRef, SECTIONS};
 use crate::ui::app::{load_commands_in_background, ActiveState, AppState, Navigation};
 use crate::ui::events::{Event, EventContext, EventController, EventfulWidget, IStatefulWidget};
+use crate::ui::keymap::{self, Action, Context};
+use crate::ui::popups;
 use crate::ui::theme::get_theme;
 
 macro_rules! select_section {
     ($state:expr, $ctx:expr, $section:expr) => {
         if $state.section_list.selected != Some($section) {
-            $state.loaded_commands = None;
+            $state.loaded_commands = collection_pages(&$ctx.collections, $section);
             $state.section_list.select(Some($section));
             $state.command_list.select(None);
             $state.search = String::new();
-            load_commands_in_background($ctx, $section);
+            if $state.loaded_commands.is_none() {
+                load_commands_in_background($ctx, $section);
+            }
         }
     };
 }
 
+/// Returns the pages of the collection at a sidebar entry,
+/// or `None` if the entry is a man section.
+fn collection_pages(collections: &Collections, entry: usize) -> Option<Vec<String>> {
+    let collection = collections.get(entry.checked_sub(SECTIONS.len())?)?;
+    Some(collection.pages.iter().map(ToString::to_string).collect())
+}
+
 pub(crate) struct ListPage {
     commands: IStatefulWidget<Commands>,
     search: IStatefulWidget<Search>,
@@ -45,6 +61,14 @@ impl EventfulWidget<AppState, Event> for ListPage {
     }
 
     fn on_event(ctx: EventContext, state: &mut AppState, _: Option<Rect>) {
+        if !matches!(state.active_state, ActiveState::List(_)) {
+            return;
+        }
+
+        if popups::on_event(&ctx, state) {
+            return;
+        }
+
         let ActiveState::List(page_state) = &mut state.active_state else {
             return;
         };
@@ -53,16 +77,33 @@ impl EventfulWidget<AppState, Event> for ListPage {
             return;
         };
 
-        match key.code {
-            KeyCode::Char('j') | KeyCode::Down if !page_state.search_active => {
+        if page_state.search_active {
+            match keymap::action(Context::Search, key) {
+                Some(Action::ConfirmSearch) => page_state.search_active = false,
+                Some(Action::DeleteChar) => {
+                    page_state.search.pop();
+                }
+                _ => {
+                    if let KeyCode::Char(ch) = key.code {
+                        page_state.command_list.selected = None;
+                        page_state.search.push(ch);
+                    }
+                }
+            }
+            return;
+        }
+
+        match keymap::action(Context::List, key) {
+            Some(Action::ScrollDown) => {
                 if page_state.section_active {
-                    let s = min(page_state.section_list.selected.unwrap() + 1, 8);
+                    let last = page_state.num_sections() - 1;
+                    let s = min(page_state.section_list.selected.unwrap() + 1, last);
                     select_section!(page_state, state, s);
                 } else {
                     page_state.scroll_down();
                 }
             }
-            KeyCode::Char('k') | KeyCode::Up if !page_state.search_active => {
+            Some(Action::ScrollUp) => {
                 if page_state.section_active {
                     let s = page_state.section_list.selected.unwrap().saturating_sub(1);
                     select_section!(page_state, state, s);
@@ -70,73 +111,65 @@ impl EventfulWidget<AppState, Event> for ListPage {
                     page_state.scroll_up();
                 }
             }
-            KeyCode::Char('d')
-                if key.modifiers == KeyModifiers::CONTROL && !page_state.search_active =>
-            {
+            Some(Action::HalfPageDown) => {
                 for _ in 0..page_state.num_elements / 2 {
                     page_state.command_list.next();
                 }
             }
-            KeyCode::Char('u')
-                if key.modifiers == KeyModifiers::CONTROL && !page_state.search_active =>
-            {
+            Some(Action::HalfPageUp) => {
                 for _ in 0..page_state.num_elements / 2 {
                     page_state.command_list.previous();
                 }
             }
-            KeyCode::Char('1') if !page_state.search_active => {
-                select_section!(page_state, state, 0);
-            }
-            KeyCode::Char('2') if !page_state.search_active => {
-                select_section!(page_state, state, 1);
-            }
-            KeyCode::Char('3') if !page_state.search_active => {
-                select_section!(page_state, state, 2);
-            }
-            KeyCode::Char('4') if !page_state.search_active => {
-                select_section!(page_state, state, 3);
+            Some(Action::SelectSection(section)) => {
+                select_section!(page_state, state, section);
             }
-            KeyCode::Char('5') if !page_state.search_active => {
-                select_section!(page_state, state, 4);
-            }
-            KeyCode::Char('6') if !page_state.search_active => {
-                select_section!(page_state, state, 5);
-            }
-            KeyCode::Char('7') if !page_state.search_active => {
-                select_section!(page_state, state, 6);
-            }
-            KeyCode::Char('8') if !page_state.search_active => {
-                select_section!(page_state, state, 7);
-            }
-            KeyCode::Char('9') if !page_state.search_active => {
-                select_section!(page_state, state, 8);
-            }
-            KeyCode::Enter if !page_state.search_active => {
+            Some(Action::Open) => {
                 if page_state.section_active {
                     page_state.section_active = false;
                     return;
                 }
-                Navigation::navigate_to(&Navigation::Reader, state, ctx.controller);
+                Navigation::navigate_to(Navigation::Reader, state, ctx.controller);
             }
-            KeyCode::Char('/') if !page_state.search_active => {
+            Some(Action::OpenInNewTab) => {
+                if let Some(page) = page_state.selected_page() {
+                    Navigation::navigate_to(Navigation::NewTab(page), state, ctx.controller);
+                }
+            }
+            Some(Action::Search) => {
                 page_state.search_active = true;
                 page_state.command_list.selected = None;
             }
-            KeyCode::Esc | KeyCode::Enter if page_state.search_active => {
-                page_state.search_active = false;
-            }
-            KeyCode::Esc if !page_state.search_active => {
+            Some(Action::Back) => {
                 page_state.search = String::new();
                 page_state.command_list.selected = None;
             }
-            KeyCode::Backspace if page_state.search_active => {
-                page_state.search.pop();
+            Some(Action::Star) => {
+                let page = page_state.selected_page();
+                state.toggle_favorite(page);
             }
-            KeyCode::Char(ch) if page_state.search_active => {
-                page_state.command_list.selected = None;
-                page_state.search.push(ch);
+            Some(Action::Collections) => {
+                let page = page_state.selected_page();
+                popups::open_collections(page, state);
             }
-            _ => {}
+            Some(Action::RemoveFromCollection) => {
+                let entry = page_state.selected_section_index();
+                let Some(page) = page_state.selected_page() else {
+                    return;
+                };
+                let Some(collection) = entry
+                    .checked_sub(SECTIONS.len())
+                    .and_then(|index| state.collections.get(index))
+                else {
+                    return;
+                };
+                let name = collection.name.clone();
+                state.collections.remove(&name, &page);
+                state.notify(format!("Removed {page} from {name}"));
+                state.save_collections();
+            }
+            Some(action) => popups::open(action, Context::List, state),
+            None => {}
         }
     }
 }
@@ -149,32 +182,79 @@ pub(crate) struct ListPageState {
     num_elements: u16,
     search_active: bool,
     search: String,
-    page_width: usize,
     throbber: ThrobberState,
     section_active: bool,
+    /// The names and sizes of the collections shown in the sidebar.
+    collections: Vec<(String, usize)>,
+    /// The commands with a page in the local tldr cache.
+    tldr: Arc<OnceLock<HashSet<String>>>,
 }
 
 impl ListPageState {
+    /// Creates a list that shows the first section.
     pub(crate) fn new(state: &mut AppState) -> Self {
-        let mut command_list = ListState::default();
-        command_list.select(state.selected_command);
-
         let mut section_list = ListState::default();
-        section_list.select(Some(state.selected_section));
+        section_list.select(Some(0));
 
-        Self {
+        let mut page_state = Self {
             loaded_commands: state.loaded_commands.clone(),
-            command_list,
+            command_list: ListState::default(),
             section_list,
             num_elements: 0,
             search_active: false,
             section_active: false,
-            search: state.command_search.clone(),
-            page_width: 0,
+            search: String::new(),
             throbber: ThrobberState::default(),
+            collections: Vec::new(),
+            tldr: Arc::clone(&state.tldr),
+        };
+        page_state.refresh(state);
+
+        page_state
+    }
+
+    /// Updates the collections in the sidebar of a list that was hidden,
+    /// e.g. while reading.
+    pub(crate) fn refresh(&mut self, state: &AppState) {
+        if let Some(section) = self.refresh_collection(&state.collections) {
+            load_commands_in_background(state, section);
         }
     }
 
+    fn num_sections(&self) -> usize {
+        SECTIONS.len() + self.collections.len()
+    }
+
+    /// Updates the collections in the sidebar and reloads the shown collection.
+    /// Returns the section that must be loaded if the shown collection was deleted.
+    pub(crate) fn refresh_collection(&mut self, collections: &Collections) -> Option<usize> {
+        self.collections = collections
+            .iter()
+            .map(|collection| (collection.name.clone(), collection.pages.len()))
+            .collect();
+
+        let entry = self.selected_section_index();
+        if entry < SECTIONS.len() {
+            return None;
+        }
+
+        if let Some(pages) = collection_pages(collections, entry) {
+            if let Some(selected) = self.command_list.selected {
+                let last = pages.len().checked_sub(1);
+                self.command_list
+                    .select(last.map(|last| selected.min(last)));
+            }
+            self.loaded_commands = Some(pages);
+            return None;
+        }
+
+        // The collection was deleted, fall back to the first section.
+        self.section_list.select(Some(0));
+        self.command_list.select(None);
+        self.loaded_commands = None;
+        Some(0)
+    }
+
     fn filtered_commands(&self) -> Option<Vec<String>> {
         let Some(commands) = &self.loaded_commands else {
             return None;
@@ -197,10 +277,6 @@ impl ListPageState {
         self.loaded_commands = Some(commands.to_vec());
     }
 
-    pub(crate) fn page_width(&self) -> usize {
-        self.page_width
-    }
-
     pub(crate) fn selected_command(&self) -> Option<String> {
         let Some(commands) = &self.filtered_commands() else {
             return None;
@@ -209,16 +285,16 @@ impl ListPageState {
         self.command_list.selected.map(|i| commands[i].clone())
     }
 
-    pub(crate) fn selected_command_index(&self) -> Option<usize> {
-        self.command_list.selected
+    pub(crate) fn is_searching(&self) -> bool {
+        self.search_active
     }
 
-    pub(crate) fn selected_section_index(&self) -> usize {
-        self.section_list.selected.unwrap_or_default()
+    pub(crate) fn selected_page(&self) -> Option<PageRef> {
+        self.selected_command()?.parse().ok()
     }
 
-    pub(crate) fn command_search(&self) -> String {
-        self.search.to_string()
+    pub(crate) fn selected_section_index(&self) -> usize {
+        self.section_list.selected.unwrap_or_default()
     }
 
     fn scroll_down(&mut self) {
@@ -237,8 +313,6 @@ impl StatefulWidgetRef for ListPage {
         let theme = get_theme();
         buf.set_style(area, theme.base);
 
-        state.page_width = area.width as usize;
-
         let [main, search] = Layout::default()
             .direction(Direction::Vertical)
             .constraints([Constraint::Min(0), Constraint::Length(1)])
@@ -287,6 +361,10 @@ impl EventfulWidget<AppState, Event> for Commands {
     }
 
     fn on_event(ctx: EventContext, state: &mut AppState, area: Option<Rect>) {
+        if state.popup.is_some() {
+            return;
+        }
+
         let ActiveState::List(page_state) = &mut state.active_state else {
             return;
         };
@@ -313,7 +391,7 @@ impl EventfulWidget<AppState, Event> for Commands {
                     let mouse_select = scroll_offset_index + diff;
                     if mouse_select < page_state.filtered_commands().map_or(0, |l| l.len()) {
                         if page_state.command_list.selected == Some(mouse_select) {
-                            Navigation::navigate_to(&Navigation::Reader, state, ctx.controller);
+                            Navigation::navigate_to(Navigation::Reader, state, ctx.controller);
                         } else {
                             page_state.command_list.select(Some(mouse_select));
                         }
@@ -357,6 +435,13 @@ impl StatefulWidgetRef for Commands {
         let builder = ListBuilder::new(|context| {
             let command = commands[context.index].clone();
 
+            // Commands are listed as `tar (1)`, collections as `tar(1)`.
+            let name = command.split(['(', ' ']).next().unwrap_or_default();
+            let has_tldr = state
+                .tldr
+                .get()
+                .is_some_and(|names| names.contains(&name.to_lowercase()));
+
             let mut line = Line::from(command);
 
             let style = if state.search_active {
@@ -369,6 +454,9 @@ impl StatefulWidgetRef for Commands {
             if context.is_selected {
                 line = line.style(theme.list.selected);
             }
+            if has_tldr {
+                line.push_span(Span::styled(" tldr", theme.marker));
+            }
 
             (line, 1)
         });
@@ -387,6 +475,10 @@ impl EventfulWidget<AppState, Event> for Section {
     }
 
     fn on_event(ctx: EventContext, state: &mut AppState, area: Option<Rect>) {
+        if state.popup.is_some() {
+            return;
+        }
+
         let ActiveState::List(page_state) = &mut state.active_state else {
             return;
         };
@@ -403,7 +495,7 @@ impl EventfulWidget<AppState, Event> for Section {
 
             if let MouseEventKind::Down(_) = e.kind {
                 let diff = position.y as usize - area.y as usize;
-                if diff < 9 {
+                if diff < page_state.num_sections() {
                     select_section!(page_state, state, diff);
                 }
             }
@@ -417,22 +509,17 @@ impl StatefulWidgetRef for Section {
     fn render_ref(&self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
         let theme = get_theme();
 
-        let sections = [
-            "(1) User commands",
-            "(2) System calls",
-            "(3) Library calls",
-            "(4) Special files",
-            "(5) File formats",
-            "(6) Games",
-            "(7) Miscellaneous",
-            "(8) System management",
-            "(9) Kernel routines",
-        ];
-
         let builder = ListBuilder::new(|context| {
-            let section = sections[context.index];
-
-            let mut line = Line::from(section);
+            let mut line = match context.index.checked_sub(SECTIONS.len()) {
+                None => {
+                    let (section, title) = SECTIONS[context.index];
+                    Line::from(format!("({section}) {title}"))
+                }
+                Some(index) => {
+                    let (name, len) = &state.collections[index];
+                    Line::from(format!("★ {name} ({len})"))
+                }
+            };
 
             if context.is_selected {
                 line = line.style(theme.list.active);
@@ -443,7 +530,7 @@ impl StatefulWidgetRef for Section {
             (line, 1)
         });
 
-        ListView::new(builder, sections.len())
+        ListView::new(builder, state.num_sections())
             .infinite_scrolling(false)
             .render(area, buf, &mut state.section_list);
     }
@@ -457,6 +544,10 @@ impl EventfulWidget<AppState, Event> for Search {
     }
 
     fn on_event(ctx: EventContext, state: &mut AppState, area: Option<Rect>) {
+        if state.popup.is_some() {
+            return;
+        }
+
         let ActiveState::List(page_state) = &mut state.active_state else {
             return;
         };
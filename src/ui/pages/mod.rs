@@ -1,9 +1,11 @@
-pub(super) mod help;
+pub(super) mod diff;
 pub(super) mod home;
+mod info;
 pub(super) mod list;
 pub(super) mod reader;
 mod utils;
 
+pub(super) use diff::{DiffPage, DiffPageState};
 pub(super) use home::{HomePage, HomePageState};
 pub(super) use list::{ListPage, ListPageState};
 pub(super) use reader::{ReaderPage, ReaderPageState};
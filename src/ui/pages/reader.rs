@@ -1,11 +1,20 @@
 #![allow(clippy::cast_possible_truncation)]
 #![allow(clippy::cast_sign_loss, clippy::cast_precision_loss)]
-use crate::core::read_command;
+use crate::core::{
+    check_file, page_source, read_help, read_page, read_piped, read_tldr, with_section, Anchor,
+    CodeBlock, Diagnostic, Footer, InfoManual, Link, Manual, Mark, Marks, Note, Notes, PageRef,
+};
 use crate::ui::app::{ActiveState, AppState, Navigation};
 use crate::ui::events::{Event, EventContext, EventController, EventfulWidget, IStatefulWidget};
+use crate::ui::external::External;
+use crate::ui::keymap::{self, Action, Context};
+use crate::ui::popups::{
+    self, ExamplesPopup, ExportPopup, IndexPopup, MarksPopup, NotePopup, PagePrompt, Popup,
+    PromptTarget,
+};
+use crate::ui::status::{display_path, status_fields, Status, StatusField};
 use crate::ui::theme::get_theme;
-use ansi_to_tui::IntoText;
-use arboard::Clipboard;
+use anyhow::{anyhow, Result};
 use ratatui::buffer::Buffer;
 use ratatui::crossterm::event::{KeyCode, KeyModifiers, MouseEventKind};
 use ratatui::prelude::*;
@@ -13,16 +22,27 @@ use ratatui::widgets::{
     Block, Borders, Padding, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState,
     StatefulWidgetRef,
 };
-use std::cmp::min;
+use std::{
+    cmp::min,
+    env, fs, mem,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, TryRecvError},
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
 
+use super::home::HomePageState;
+use super::info::InfoView;
 use super::utils::{
-    extract_text_from_lines, find_matches, text_to_lines, PositionAbsolut, PositionScreen,
+    extract_text_from_lines, find_matches, line_bounds, word_at, PositionAbsolut, PositionScreen,
     Selection,
 };
 
 pub(crate) struct ReaderPage {
-    content: IStatefulWidget<Content>,
-    search: IStatefulWidget<Search>,
+    content: IStatefulWidget<Content<false>>,
+    search: IStatefulWidget<Search<false>>,
+    split_content: IStatefulWidget<Content<true>>,
+    split_search: IStatefulWidget<Search<true>>,
 }
 
 impl ReaderPage {
@@ -30,13 +50,17 @@ impl ReaderPage {
         Self {
             content: IStatefulWidget::new(Content, controller),
             search: IStatefulWidget::new(Search, controller),
+            split_content: IStatefulWidget::new(Content, controller),
+            split_search: IStatefulWidget::new(Search, controller),
         }
     }
 }
 
 #[derive(Default)]
 pub(crate) struct ReaderPageState {
-    text: Text<'static>,
+    page: Option<PageRef>,
+    manual: Manual,
+    lines: Vec<String>,
     scroll_offset: usize,
     page_height: usize,
     num_lines: usize,
@@ -48,28 +72,244 @@ pub(crate) struct ReaderPageState {
     matches: Vec<(u16, u16)>,
     selection: Option<Selection>,
     selection_active: bool,
-    clipboard: Option<Clipboard>,
+    /// The last click and how many clicks came in a row, to select words and lines.
+    last_click: Option<(Instant, PositionAbsolut, u8)>,
+    /// The screen position of the content, set on render.
     padding_x: u16,
     padding_y: u16,
+    /// The notes on the page and the lines they are shown at.
+    notes: Vec<(usize, Note)>,
+    /// The marks on the page and the jumps between them.
+    jumps: Box<Jumps>,
+    cursor: Option<usize>,
+    /// The right pane of the split view.
+    split: Option<Box<ReaderPageState>>,
+    /// Whether the right pane has the focus.
+    focus_split: bool,
+    /// Whether scrolling one pane scrolls the other as well.
+    sync_scroll: bool,
+    origin: Origin,
+    /// The warnings from rendering a page file.
+    diagnostics: Vec<Diagnostic>,
+    show_diagnostics: bool,
+    /// The man page and its scroll offset while its tldr page is shown.
+    man_page: Option<(Manual, usize)>,
+    /// What the status line shows besides the reading position.
+    metadata: Box<Metadata>,
+    /// The width of the terminal the page was rendered for, split between
+    /// both panes in the split view.
+    width: usize,
+}
+
+/// The headings and the footer of the displayed text, its source file,
+/// which is looked up once the status line shows it, and whether the page
+/// has an info manual.
+#[derive(Default)]
+struct Metadata {
+    headings: Vec<(usize, String)>,
+    footer: Option<Footer>,
+    source: Lookup<Option<String>>,
+    info: Lookup<bool>,
+}
+
+impl Metadata {
+    fn new(manual: &Manual) -> Self {
+        Self {
+            headings: manual.headings(),
+            footer: manual.footer(),
+            source: Lookup::default(),
+            info: Lookup::default(),
+        }
+    }
+}
+
+/// A value that is looked up in the background, since it runs `man` or
+/// reads many files, e.g. the source file of a page.
+#[derive(Default)]
+enum Lookup<T> {
+    #[default]
+    NotStarted,
+    Running(mpsc::Receiver<T>),
+    Done(T),
+}
+
+impl<T: Default + Send + 'static> Lookup<T> {
+    fn start(lookup: impl FnOnce() -> T + Send + 'static) -> Self {
+        let (sx, value) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = sx.send(lookup());
+        });
+        Lookup::Running(value)
+    }
+
+    /// Returns the value once the lookup finished.
+    fn get(&mut self) -> Option<&T> {
+        if let Lookup::Running(value) = self {
+            *self = match value.try_recv() {
+                Ok(value) => Lookup::Done(value),
+                Err(TryRecvError::Empty) => return None,
+                Err(TryRecvError::Disconnected) => Lookup::Done(T::default()),
+            };
+        }
+        match self {
+            Lookup::Done(value) => Some(value),
+            Lookup::NotStarted | Lookup::Running(_) => None,
+        }
+    }
+}
+
+/// Looks up in the background whether the page has an info manual.
+fn lookup_info(page: Option<&PageRef>) -> Lookup<bool> {
+    match page {
+        Some(page) => {
+            let name = page.name.clone();
+            Lookup::start(move || InfoManual::exists(&name))
+        }
+        None => Lookup::Done(false),
+    }
+}
+
+/// The marks of the displayed page and the state of jumping to them.
+#[derive(Default)]
+struct Jumps {
+    /// The marks and the lines they point to.
+    marks: Vec<(usize, Mark)>,
+    /// `m` or `'` while waiting for the letter of a mark.
+    pending: Option<Action>,
+    /// The top line before the last jump, to go back with `''`.
+    last: Option<usize>,
+}
+
+/// Where the displayed page comes from.
+#[derive(Default)]
+enum Origin {
+    /// A man page, rendered by `man`.
+    #[default]
+    Page,
+    /// A page piped to stdin, which can't be rendered again. It is shown as
+    /// it arrives until the pipe closes.
+    Piped {
+        input: Vec<u8>,
+        chunks: Option<mpsc::Receiver<Vec<u8>>>,
+    },
+    /// A man page file, rendered again whenever it changes if `watch` is set.
+    File {
+        path: PathBuf,
+        watch: bool,
+        modified: Option<SystemTime>,
+    },
+    /// A command without a man page whose `--help` output is read in the
+    /// background. Shows the error of `man` if it has none.
+    HelpPending {
+        help: mpsc::Receiver<Option<(String, Manual)>>,
+        error: Manual,
+    },
+    /// The `--help` output of a command without a man page, with the
+    /// command that printed it, e.g. `tar -h`.
+    Help(String),
+    /// A node of an info manual.
+    Info(Box<InfoView>),
+}
+
+/// What arrived on stdin since the last tick.
+enum PipedInput {
+    Nothing,
+    /// More of the page, which is shown already.
+    Text,
+    /// The end of the page.
+    End,
+    /// The end without any text, e.g. from `/dev/null`.
+    Empty,
 }
 
 impl ReaderPageState {
-    pub(crate) fn new(command: &str, width: usize) -> Self {
-        let reduced_width = (width as f64 * 0.9) as u16;
+    /// Shows a man page, or the `--help` output of a command without one.
+    /// Running the command may take a while, thus it runs in the background.
+    pub(crate) fn new(page: &PageRef, width: usize) -> Self {
+        let (found, manual) = Self::read(page, width);
+        if found.is_none() && page.section.is_none() {
+            let (sx, help) = mpsc::channel();
+            let name = page.name.clone();
+            let help_width = man_width(width);
+            thread::spawn(move || {
+                let _ = sx.send(read_help(&name, &help_width).ok());
+            });
+
+            let loading = Manual::plain(&format!("Reading the --help output of {}...", page.name));
+            return Self {
+                origin: Origin::HelpPending {
+                    help,
+                    error: manual,
+                },
+                width,
+                ..Self::with_manual(Some(page.clone()), loading)
+            };
+        }
+        Self {
+            width,
+            ..Self::with_manual(found, manual)
+        }
+    }
 
-        let text = match read_command(command, &(format!("{reduced_width}"))) {
-            Ok(ansi) => ansi
-                .into_text()
-                .unwrap_or(Text::from("Failed to convert ansi to tui.")),
-            Err(err) => Text::from(format!("failed to read command: {err}")),
+    /// Shows a page piped to stdin, e.g. by `man` when mantui is the `MANPAGER`.
+    /// The page is shown as it arrives, see `receive_piped`.
+    pub(crate) fn piped(chunks: mpsc::Receiver<Vec<u8>>) -> Self {
+        Self {
+            origin: Origin::Piped {
+                input: Vec::new(),
+                chunks: Some(chunks),
+            },
+            ..Self::with_manual(None, Manual::plain("Reading from stdin..."))
+        }
+    }
+
+    /// Shows a man page file, e.g. `./docs/foo.1`, with the warnings of the formatter.
+    pub(crate) fn file(path: &Path, watch: bool, width: usize) -> Self {
+        let (manual, diagnostics) = match check_file(path, &man_width(width)) {
+            Ok(rendered) => rendered,
+            Err(err) => (
+                Manual::plain(&format!("failed to read {}: {err}", path.display())),
+                Vec::new(),
+            ),
         };
 
         Self {
+            origin: Origin::File {
+                path: path.to_path_buf(),
+                watch,
+                modified: modified(path),
+            },
+            diagnostics,
+            show_diagnostics: true,
+            width,
+            ..Self::with_manual(None, manual)
+        }
+    }
+
+    /// Shows the info manual of a program, starting at its `Top` node or
+    /// the node of its `dir` entry.
+    pub(crate) fn info_manual(name: &str) -> Result<Self> {
+        let view = InfoView::open(name)?;
+        let manual = view.render();
+        Ok(Self {
+            origin: Origin::Info(Box::new(view)),
+            ..Self::with_manual(None, manual)
+        })
+    }
+
+    fn with_manual(page: Option<PageRef>, manual: Manual) -> Self {
+        let lines = manual.plain_lines();
+        let mut metadata = Box::new(Metadata::new(&manual));
+        metadata.info = lookup_info(page.as_ref());
+
+        Self {
+            page,
             scroll_offset: 0,
             page_height: 0,
             max_scroll_pos: 0,
-            num_lines: text.lines.len(),
-            text,
+            num_lines: lines.len(),
+            manual,
+            lines,
             scrollbar: ScrollbarState::new(0).position(0),
             search: String::new(),
             matches: Vec::new(),
@@ -77,17 +317,659 @@ impl ReaderPageState {
             search_active: false,
             selection: None,
             selection_active: false,
-            clipboard: Clipboard::new().ok(),
+            last_click: None,
             padding_x: 2,
             padding_y: 1,
+            notes: Vec::new(),
+            jumps: Box::default(),
+            cursor: None,
+            split: None,
+            focus_split: false,
+            sync_scroll: false,
+            origin: Origin::Page,
+            diagnostics: Vec::new(),
+            show_diagnostics: false,
+            man_page: None,
+            metadata,
+            width: 0,
         }
     }
+
+    fn read(page: &PageRef, width: usize) -> (Option<PageRef>, Manual) {
+        match read_page(page, &man_width(width)) {
+            Ok(manual) => (Some(page.clone()), manual),
+            Err(err) => (
+                None,
+                Manual::plain(&format!("failed to read command: {err}")),
+            ),
+        }
+    }
+
+    /// Renders the page again for a new width and keeps the position.
+    fn reload(&mut self, width: usize) {
+        let manual = match &self.origin {
+            // Help output and info nodes are formatted already.
+            Origin::Piped { .. }
+            | Origin::HelpPending { .. }
+            | Origin::Help(_)
+            | Origin::Info(_) => return,
+            Origin::Page => {
+                let Some(page) = self.page.clone() else {
+                    return;
+                };
+                match Self::read(&page, width) {
+                    (Some(_), manual) => manual,
+                    (None, _) => return,
+                }
+            }
+            // A file that can't be rendered, e.g. while it is written, keeps the last version.
+            Origin::File { path, .. } => match check_file(path, &man_width(width)) {
+                Ok((manual, diagnostics)) => {
+                    self.diagnostics = diagnostics;
+                    manual
+                }
+                Err(err) => {
+                    self.diagnostics = vec![Diagnostic {
+                        line: None,
+                        message: format!("{err:#}"),
+                    }];
+                    return;
+                }
+            },
+        };
+
+        let position = self.position();
+        match &mut self.man_page {
+            Some((man_page, offset)) => {
+                *offset = manual.resolve(&position).unwrap_or_default();
+                *man_page = manual;
+            }
+            None => {
+                self.show(manual);
+                self.scroll_offset = self.resolve(&position).unwrap_or_default();
+            }
+        }
+        self.notes = mem::take(&mut self.notes)
+            .into_iter()
+            .filter_map(|(_, note)| Some((self.resolve(&note.anchor)?, note)))
+            .collect();
+        self.jumps.marks = mem::take(&mut self.jumps.marks)
+            .into_iter()
+            .filter_map(|(_, mark)| Some((self.resolve(&mark.anchor)?, mark)))
+            .collect();
+    }
+
+    /// Replaces the displayed text and returns the previous one.
+    fn show(&mut self, manual: Manual) -> Manual {
+        self.lines = manual.plain_lines();
+        self.num_lines = self.lines.len();
+        self.matches = find_matches(&self.lines, &self.search);
+        self.selected_match = None;
+        self.selection = None;
+        self.cursor = None;
+        self.jumps.last = None;
+        self.metadata.headings = manual.headings();
+        self.metadata.footer = manual.footer();
+        mem::replace(&mut self.manual, manual)
+    }
+
+    /// Switches between the man page and its tldr page.
+    ///
+    /// Returns false if the tldr cache has no page for it.
+    pub(crate) fn toggle_tldr(&mut self) -> bool {
+        if let Some((manual, offset)) = self.man_page.take() {
+            self.show(manual);
+            self.scroll_offset = offset;
+            return true;
+        }
+
+        let Some(tldr) = self.page.as_ref().and_then(|page| read_tldr(&page.name)) else {
+            return false;
+        };
+        let man_page = self.show(tldr);
+        self.man_page = Some((man_page, self.scroll_offset));
+        self.scroll_offset = 0;
+        true
+    }
+
+    /// Returns the man page, also while its tldr page is shown.
+    fn man(&self) -> &Manual {
+        self.man_page
+            .as_ref()
+            .map_or(&self.manual, |(manual, _)| manual)
+    }
+
+    /// Navigates the info manual and shows the node it went to, scrolled to
+    /// the returned line. `navigate` gets the info view and the scroll offset.
+    pub(crate) fn navigate_info(
+        &mut self,
+        navigate: impl FnOnce(&mut InfoView, usize) -> Result<usize>,
+    ) -> Result<()> {
+        let offset = self.scroll_offset;
+        let Origin::Info(view) = &mut self.origin else {
+            return Ok(());
+        };
+
+        let line = navigate(view, offset)?;
+        let manual = view.render();
+        self.show(manual);
+        self.scroll_offset = line;
+        Ok(())
+    }
+
+    /// Goes to a node of the info manual in the focused pane, e.g. from the index.
+    pub(crate) fn go_to_info_node(&mut self, node: &str, line: Option<usize>) -> Result<()> {
+        self.focused_mut().navigate_info(|view, offset| {
+            view.go(None, node, offset)?;
+            Ok(line.unwrap_or_default().saturating_sub(1))
+        })
+    }
+
+    fn info(&self) -> Option<&InfoView> {
+        match &self.origin {
+            Origin::Info(view) => Some(view),
+            _ => None,
+        }
+    }
+
+    /// Handles the navigation in an info manual. Returns `None` for other actions.
+    fn info_action(&mut self, action: Action) -> Option<Result<()>> {
+        let link = match action {
+            Action::NextNode => Link::Next,
+            Action::PreviousNode => Link::Prev,
+            Action::UpNode => Link::Up,
+            Action::BackNode => {
+                return Some(self.navigate_info(|view, _| {
+                    view.back()
+                        .ok_or_else(|| anyhow!("No previously visited node"))
+                }));
+            }
+            Action::NextReference | Action::PreviousReference => {
+                self.select_reference(action == Action::NextReference);
+                return Some(Ok(()));
+            }
+            Action::Open => {
+                let reference = self.info()?.selected()?.clone();
+                return Some(
+                    self.navigate_info(|view, offset| view.follow(&reference, offset).map(|()| 0)),
+                );
+            }
+            _ => return None,
+        };
+        Some(self.navigate_info(|view, offset| view.go_to_link(link, offset).map(|()| 0)))
+    }
+
+    /// Selects the next or previous reference of the info node and scrolls to it.
+    fn select_reference(&mut self, forward: bool) {
+        let offset = self.scroll_offset;
+        let Origin::Info(view) = &mut self.origin else {
+            return;
+        };
+        if let Some(line) = view.select_reference(forward, offset) {
+            let visible = offset..offset + self.page_height;
+            if !visible.contains(&line) {
+                self.scroll_offset = line.saturating_sub(self.page_height / 2);
+            }
+        }
+    }
+
+    /// Follows the reference at a position of the info node, if there is one.
+    fn follow_reference_at(&mut self, line: usize, column: usize) -> Option<Result<()>> {
+        let reference = self.info()?.reference_at(line, column)?.clone();
+        Some(self.navigate_info(|view, offset| view.follow(&reference, offset).map(|()| 0)))
+    }
+
+    /// Renders the pages again if the width of the terminal changed. Both
+    /// panes of the split view get half of it.
+    pub(crate) fn resize(&mut self, width: usize) {
+        if self.width == width {
+            return;
+        }
+        self.width = width;
+
+        let width = if self.split.is_some() {
+            width / 2
+        } else {
+            width
+        };
+        self.reload(width);
+        if let Some(split) = self.split.as_deref_mut() {
+            split.reload(width);
+        }
+    }
+
+    /// Shows the `--help` output once it was read in the background, or the
+    /// error of `man` if the command printed none.
+    pub(crate) fn receive_help(&mut self) {
+        if let Some(split) = self.split.as_deref_mut() {
+            split.receive_help();
+        }
+
+        let Origin::HelpPending { help, error } = &mut self.origin else {
+            return;
+        };
+        let help = match help.try_recv() {
+            Ok(help) => help,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => None,
+        };
+
+        match help {
+            Some((command, manual)) => {
+                self.origin = Origin::Help(command);
+                self.show(manual);
+            }
+            None => {
+                let error = mem::take(error);
+                self.origin = Origin::Page;
+                self.page = None;
+                self.metadata.info = Lookup::Done(false);
+                self.show(error);
+            }
+        }
+    }
+
+    /// Shows the page piped to stdin as far as it arrived. The page is named
+    /// once its name is known, from `MAN_PN` or from its header.
+    fn receive_piped(&mut self) -> PipedInput {
+        let Origin::Piped { input, chunks } = &mut self.origin else {
+            return PipedInput::Nothing;
+        };
+        let Some(receiver) = chunks else {
+            return PipedInput::Nothing;
+        };
+
+        let mut received = false;
+        let end = loop {
+            match receiver.try_recv() {
+                Ok(chunk) => {
+                    input.extend(chunk);
+                    received = true;
+                }
+                Err(TryRecvError::Empty) => break false,
+                Err(TryRecvError::Disconnected) => {
+                    *chunks = None;
+                    break true;
+                }
+            }
+        };
+        if end && input.iter().all(u8::is_ascii_whitespace) {
+            return PipedInput::Empty;
+        }
+        if !received {
+            return if end {
+                PipedInput::End
+            } else {
+                PipedInput::Nothing
+            };
+        }
+
+        let (page, manual) = read_piped(input, env::var("MAN_PN").ok().as_deref());
+        if self.page.is_none() && page.is_some() {
+            self.metadata.info = lookup_info(page.as_ref());
+            self.page = page;
+        }
+        self.show(manual);
+        if end {
+            PipedInput::End
+        } else {
+            PipedInput::Text
+        }
+    }
+
+    /// Renders watched files again if they changed on disk.
+    pub(crate) fn reload_changed(&mut self, width: usize) {
+        let width = if self.split.is_some() {
+            width / 2
+        } else {
+            width
+        };
+        if self.file_changed() {
+            self.reload(width);
+        }
+        if let Some(split) = self.split.as_deref_mut() {
+            if split.file_changed() {
+                split.reload(width);
+            }
+        }
+    }
+
+    /// Whether the displayed file is watched and was modified since it was read.
+    fn file_changed(&mut self) -> bool {
+        let Origin::File {
+            path,
+            watch: true,
+            modified: last,
+        } = &mut self.origin
+        else {
+            return false;
+        };
+
+        let current = modified(path);
+        if current.is_none() || current == *last {
+            return false;
+        }
+        *last = current;
+        true
+    }
+
+    /// Shows or hides the warnings of a page file.
+    ///
+    /// Returns false if there are none.
+    pub(crate) fn toggle_diagnostics(&mut self) -> bool {
+        if self.diagnostics.is_empty() {
+            return false;
+        }
+        self.show_diagnostics = !self.show_diagnostics;
+        true
+    }
+
+    /// Returns the source file of the page and whether to open it read-only.
+    /// Installed pages are read-only, page files are not.
+    fn source(&self) -> Result<(PathBuf, bool)> {
+        match (&self.origin, &self.page) {
+            (Origin::File { path, .. }, _) => Ok((path.clone(), false)),
+            (Origin::Page, Some(page)) => Ok((page_source(page)?, true)),
+            _ => Err(anyhow!("No source file for this page")),
+        }
+    }
+
+    /// The displayed page, if it could be read.
+    pub(crate) fn page(&self) -> Option<&PageRef> {
+        self.page.as_ref()
+    }
+
+    /// The displayed page with its section, which the history, notes and
+    /// marks are saved for, such that `tar` and `tar(1)` share them.
+    pub(crate) fn saved_page(&self) -> Option<PageRef> {
+        self.page
+            .as_ref()
+            .map(|page| with_section(page, self.man()))
+    }
+
+    /// Returns the title of the page, or of both pages in the split view.
+    pub(crate) fn title(&self) -> Option<String> {
+        let page = self.name()?;
+        match self.split.as_ref().and_then(|split| split.title()) {
+            Some(split) => Some(format!("{page} │ {split}")),
+            None => Some(page),
+        }
+    }
+
+    /// Returns the name of the displayed page, e.g. `tar(1)` or `tar(1) tldr`.
+    fn name(&self) -> Option<String> {
+        let page = match (&self.page, &self.origin) {
+            (Some(page), _) => page.to_string(),
+            (None, Origin::Piped { .. }) => String::from("stdin"),
+            (None, Origin::File { path, .. }) => path
+                .file_name()
+                .unwrap_or(path.as_os_str())
+                .to_string_lossy()
+                .to_string(),
+            (None, Origin::Info(view)) => view.title(),
+            (None, Origin::Page | Origin::HelpPending { .. } | Origin::Help(_)) => return None,
+        };
+        let page = match (&self.man_page, &self.origin) {
+            (Some(_), _) => format!("{page} tldr"),
+            (None, Origin::Help(command)) => command.clone(),
+            (None, _) => page,
+        };
+        Some(page)
+    }
+
+    /// The headings of the displayed text with their lines.
+    pub(crate) fn headings(&self) -> &[(usize, String)] {
+        &self.focused().metadata.headings
+    }
+
+    /// Returns the page and the reading position for the status line.
+    fn status(&mut self, fields: &[StatusField]) -> Status {
+        if fields.contains(&StatusField::Source) {
+            self.lookup_source();
+        }
+        let source = self.metadata.source.get().cloned().flatten();
+
+        let heading = self
+            .metadata
+            .headings
+            .iter()
+            .rev()
+            .find(|(line, _)| *line <= self.scroll_offset)
+            .map(|(_, heading)| heading.clone());
+        let percent = match self.max_scroll_pos {
+            0 => 100,
+            max => self.scroll_offset * 100 / max,
+        };
+        Status {
+            page: self.name(),
+            source,
+            footer: self.metadata.footer.clone(),
+            heading,
+            line: self.scroll_offset,
+            lines: self.num_lines,
+            percent,
+        }
+    }
+
+    /// Starts looking up the source file for the status line once.
+    fn lookup_source(&mut self) {
+        if !matches!(self.metadata.source, Lookup::NotStarted) {
+            return;
+        }
+        self.metadata.source = match (&self.origin, &self.page) {
+            (Origin::Page, Some(page)) => {
+                let page = page.clone();
+                Lookup::start(move || page_source(&page).ok().map(|path| display_path(&path)))
+            }
+            _ => Lookup::Done(self.source().ok().map(|(path, _)| display_path(&path))),
+        };
+    }
+
+    pub(crate) fn is_searching(&self) -> bool {
+        self.focused().search_active
+    }
+
+    /// Opens a page in a second pane. Both panes get half of the width.
+    ///
+    /// Returns the page if it could be read.
+    pub(crate) fn open_split(
+        &mut self,
+        page: &PageRef,
+        width: usize,
+        notes: &Notes,
+        marks: &Marks,
+    ) -> Option<PageRef> {
+        let half = width / 2;
+        if self.split.is_none() {
+            self.reload(half);
+        }
+
+        let mut split = ReaderPageState::new(page, half);
+        split.set_notes(notes);
+        split.set_marks(marks);
+        let page = split.saved_page();
+        self.split = Some(Box::new(split));
+        self.focus_split = true;
+        page
+    }
+
+    /// Closes the second pane and gives the full width back to the first.
+    pub(crate) fn close_split(&mut self, width: usize) {
+        if self.split.take().is_some() {
+            self.focus_split = false;
+            self.reload(width);
+        }
+    }
+
+    /// Returns the pane with the focus.
+    fn focused(&self) -> &ReaderPageState {
+        match &self.split {
+            Some(split) if self.focus_split => split,
+            _ => self,
+        }
+    }
+
+    fn focused_mut(&mut self) -> &mut ReaderPageState {
+        let focus_split = self.focus_split;
+        self.pane_mut(focus_split).unwrap()
+    }
+
+    /// Returns the right pane if `split` is set, otherwise the left pane.
+    fn pane_mut(&mut self, split: bool) -> Option<&mut ReaderPageState> {
+        if split {
+            self.split.as_deref_mut()
+        } else {
+            Some(self)
+        }
+    }
+
+    /// Scrolls a pane, and the other pane too if scrolling is synchronized.
+    fn scroll(&mut self, split: bool, scroll: fn(&mut ReaderPageState)) {
+        if let Some(pane) = self.pane_mut(split) {
+            scroll(pane);
+        }
+        if self.sync_scroll {
+            if let Some(pane) = self.pane_mut(!split) {
+                scroll(pane);
+            }
+        }
+    }
+
+    /// Returns the anchor of the first visible line.
+    pub(crate) fn position(&self) -> Anchor {
+        match &self.man_page {
+            Some((manual, offset)) => manual.anchor(*offset),
+            None => self.manual.anchor(self.scroll_offset),
+        }
+    }
+
+    /// Returns the line of an anchor in the man page.
+    pub(crate) fn resolve(&self, anchor: &Anchor) -> Option<usize> {
+        self.man().resolve(anchor)
+    }
+
+    /// Places the notes of the displayed page.
+    pub(crate) fn set_notes(&mut self, notes: &Notes) {
+        let Some(page) = self.saved_page() else {
+            return;
+        };
+
+        self.notes = notes
+            .for_page(&page)
+            .filter_map(|note| Some((self.resolve(&note.anchor)?, note.clone())))
+            .collect();
+
+        if let Some(split) = &mut self.split {
+            split.set_notes(notes);
+        }
+    }
+
+    /// Places the marks of the displayed page.
+    pub(crate) fn set_marks(&mut self, marks: &Marks) {
+        let Some(page) = self.saved_page() else {
+            return;
+        };
+
+        self.jumps.marks = marks
+            .for_page(&page)
+            .filter_map(|mark| Some((self.resolve(&mark.anchor)?, mark.clone())))
+            .collect();
+
+        if let Some(split) = &mut self.split {
+            split.set_marks(marks);
+        }
+    }
+
+    /// Returns the page that marks are set on, which is only known for
+    /// man pages. It always has its section, such that marks set on `tar`
+    /// show up on `tar(1)`.
+    fn mark_page(&self) -> Option<PageRef> {
+        self.saved_page().filter(|_| self.man_page.is_none())
+    }
+
+    /// Returns the popup that lists the marks of the focused pane.
+    pub(crate) fn marks_popup(&self) -> Option<MarksPopup> {
+        let state = self.focused();
+        if state.man_page.is_some() || state.jumps.marks.is_empty() {
+            return None;
+        }
+        let name = state.name().unwrap_or_else(|| String::from("page"));
+        Some(MarksPopup::new(&name, state.jumps.marks.clone()))
+    }
+
+    /// Returns the clicked line if it is visible, otherwise the first visible line.
+    fn current_line(&self) -> usize {
+        let visible = self.scroll_offset..self.scroll_offset + self.page_height;
+        self.cursor
+            .filter(|line| visible.contains(line))
+            .unwrap_or(self.scroll_offset)
+    }
+
+    /// Returns the example at the clicked or top line, or else the first one
+    /// below it on the screen.
+    fn code_block(&self) -> Option<CodeBlock> {
+        let line = self.current_line();
+        let end = self.scroll_offset + self.page_height;
+        self.manual
+            .code_blocks()
+            .into_iter()
+            .find(|block| block.lines.end > line && block.lines.start < end)
+    }
+
+    /// Returns the popup to show or edit the note on a line.
+    fn note_popup(&self, line: usize) -> Option<NotePopup> {
+        // Notes belong to the lines of the man page.
+        let page = self.saved_page().filter(|_| self.man_page.is_none())?;
+        let popup = match self.notes.iter().find(|(l, _)| *l == line) {
+            Some((_, note)) => NotePopup::new(page, note.anchor.clone(), note.note.clone()),
+            None => NotePopup::new(page, self.manual.anchor(line), String::new()),
+        };
+        Some(popup)
+    }
+
+    /// Scrolls the focused pane such that a line is at the top, as far as
+    /// possible. `''` goes back.
+    pub(crate) fn scroll_to(&mut self, line: usize) {
+        self.focused_mut().jump_to(line);
+    }
+
+    fn jump_to(&mut self, line: usize) {
+        self.jumps.last = Some(self.scroll_offset);
+        self.scroll_offset = line;
+    }
+
+    /// Goes back to where the last jump started. Returns `false` if there was none.
+    fn jump_back(&mut self) -> bool {
+        match self.jumps.last {
+            Some(line) => {
+                self.jump_to(line);
+                true
+            }
+            None => false,
+        }
+    }
+
     fn scroll_up(&mut self) {
         self.scroll_offset = self.scroll_offset.saturating_sub(1);
     }
     fn scroll_down(&mut self) {
         self.scroll_offset = min(self.scroll_offset + 1, self.max_scroll_pos);
     }
+    fn half_page_up(&mut self) {
+        self.scroll_offset = self.scroll_offset.saturating_sub(self.page_height / 2);
+    }
+    fn half_page_down(&mut self) {
+        self.scroll_offset = min(
+            self.scroll_offset + self.page_height / 2,
+            self.max_scroll_pos,
+        );
+    }
+    fn scroll_to_top(&mut self) {
+        self.scroll_offset = 0;
+    }
+    fn scroll_to_bottom(&mut self) {
+        self.scroll_offset = self.max_scroll_pos;
+    }
 
     pub fn select_next_search(&mut self) {
         if self.matches.is_empty() {
@@ -161,90 +1043,344 @@ impl ReaderPageState {
         None
     }
 
-    fn copy_selection(&mut self) {
-        if let Some(clipboard) = &mut self.clipboard {
-            if let Some(selection) = &self.selection.take() {
-                let lines = text_to_lines(&self.text);
-                let copied_text = extract_text_from_lines(&lines, selection);
-                let _ = clipboard.set_text(copied_text);
+    /// Counts the clicks in a row at a position: 1, 2 for a double click and
+    /// 3 for a triple click, after which it starts again.
+    fn count_clicks(&mut self, position: PositionAbsolut) -> u8 {
+        let clicks = match self.last_click {
+            Some((time, last, clicks)) if last == position && time.elapsed() < DOUBLE_CLICK => {
+                clicks % 3 + 1
             }
-        }
+            _ => 1,
+        };
+        self.last_click = Some((Instant::now(), position, clicks));
+        clicks
+    }
+
+    /// Selects the word at a position, or the text of its line.
+    fn select_at(&mut self, position: PositionAbsolut, line: bool) {
+        let text = self.lines.get(position.0.y as usize);
+        let bounds = text.and_then(|text| {
+            if line {
+                line_bounds(text)
+            } else {
+                word_at(text, position.0.x as usize)
+            }
+        });
+        self.selection = bounds.map(|(start, end)| {
+            Selection::new(
+                PositionAbsolut::new(start as u16, position.0.y),
+                PositionAbsolut::new(end as u16, position.0.y),
+            )
+        });
+        self.selection_active = self.selection.is_some();
+    }
+
+    /// The text of the mouse selection.
+    fn selected_text(&self) -> Option<String> {
+        let selection = self.selection.as_ref()?;
+        Some(extract_text_from_lines(&self.lines, selection))
     }
 }
 
+/// Sets the mark of a letter after `m`, or jumps to it after `'`.
+fn on_mark(action: Action, letter: char, app_state: &mut AppState) {
+    let ActiveState::Read(reader) = &mut app_state.active_state else {
+        return;
+    };
+    let state = reader.focused_mut();
+
+    let message = match action {
+        Action::SetMark if letter.is_ascii_alphabetic() => match state.mark_page() {
+            Some(page) => {
+                let anchor = state.manual.anchor(state.scroll_offset);
+                app_state.marks.set(&page, letter, anchor);
+                app_state.save_marks();
+                format!("Mark {letter} set")
+            }
+            None => String::from("Marks can only be set on man pages"),
+        },
+        Action::JumpToMark if letter == '\'' => {
+            if state.jump_back() {
+                return;
+            }
+            String::from("No jump to go back from")
+        }
+        Action::JumpToMark => match state
+            .jumps
+            .marks
+            .iter()
+            .find(|(_, mark)| mark.letter == letter)
+        {
+            Some((line, _)) if state.man_page.is_none() => {
+                state.jump_to(*line);
+                return;
+            }
+            _ => format!("Mark {letter} is not set"),
+        },
+        _ => return,
+    };
+    app_state.notify(message);
+}
+
+/// The longest time between the clicks of a double or triple click.
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// The width to render a page at, leaving a margin within the pane.
+fn man_width(width: usize) -> String {
+    let reduced_width = (width as f64 * 0.9) as u16;
+    reduced_width.to_string()
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
 impl EventfulWidget<AppState, Event> for ReaderPage {
     fn unique_key() -> String {
         String::from("ManPage")
     }
 
     fn on_event(ctx: EventContext, app_state: &mut AppState, _area: Option<Rect>) {
-        let ActiveState::Read(state) = &mut app_state.active_state else {
+        let width = app_state.width();
+        let ActiveState::Read(reader) = &mut app_state.active_state else {
             return;
         };
 
-        if let Event::Key(event) = ctx.event {
-            match event.code {
-                KeyCode::Char(ch)
-                    if state.search_active && event.modifiers != KeyModifiers::CONTROL =>
-                {
-                    state.selected_match = None;
-                    state.search.push(ch);
-                    state.matches = find_matches(&state.text, &state.search);
-                    state.select_next_search();
+        if let Event::Tick = ctx.event {
+            reader.resize(width);
+            reader.reload_changed(width);
+            reader.receive_help();
+            match reader.receive_piped() {
+                PipedInput::Nothing => {}
+                PipedInput::Text => {
+                    reader.set_notes(&app_state.notes);
+                    reader.set_marks(&app_state.marks);
+                }
+                PipedInput::End => app_state.open_piped(),
+                PipedInput::Empty => {
+                    let home = ActiveState::Home(HomePageState::new());
+                    app_state.replace_tab(home, ctx.controller);
                 }
-                KeyCode::Char('j') | KeyCode::Down => {
-                    state.scroll_down();
+            }
+            return;
+        }
+
+        if popups::on_event(&ctx, app_state) {
+            return;
+        }
+
+        let ActiveState::Read(reader) = &mut app_state.active_state else {
+            return;
+        };
+
+        let Event::Key(event) = ctx.event else {
+            return;
+        };
+
+        let state = reader.focused_mut();
+        if state.search_active {
+            match keymap::action(Context::Search, event) {
+                Some(Action::ConfirmSearch) => {
+                    state.search_active = false;
+                    return;
                 }
-                KeyCode::Char('k') | KeyCode::Up => {
-                    state.scroll_up();
+                Some(Action::DeleteChar) => {
+                    state.search.pop();
+                    return;
                 }
-                KeyCode::Char('d') if event.modifiers == KeyModifiers::CONTROL => {
-                    state.scroll_offset = min(
-                        state.scroll_offset + state.page_height / 2,
-                        state.max_scroll_pos,
-                    );
+                _ => {}
+            }
+
+            if let KeyCode::Char(ch) = event.code {
+                if event.modifiers != KeyModifiers::CONTROL {
+                    state.selected_match = None;
+                    state.search.push(ch);
+                    state.matches = find_matches(&state.lines, &state.search);
+                    state.select_next_search();
+                    return;
                 }
-                KeyCode::Char('u') if event.modifiers == KeyModifiers::CONTROL => {
-                    state.scroll_offset = state.scroll_offset.saturating_sub(state.page_height / 2);
+            }
+        }
+
+        // The letter after `m` or `'`.
+        if let Some(action) = state.jumps.pending.take() {
+            if let KeyCode::Char(letter) = event.code {
+                on_mark(action, letter, app_state);
+            }
+            return;
+        }
+
+        if let Some(view) = state.info() {
+            match keymap::action(Context::Info, event) {
+                Some(Action::InfoIndex) => {
+                    let entries = view.index();
+                    if entries.is_empty() {
+                        let message = format!("The info manual of {} has no index", view.name());
+                        app_state.notify(message);
+                    } else {
+                        let popup = IndexPopup::new(view.name(), entries);
+                        app_state.popup = Some(Popup::Index(popup));
+                    }
+                    return;
                 }
-                KeyCode::Char('G') if event.modifiers == KeyModifiers::SHIFT => {
-                    state.scroll_offset = state.max_scroll_pos;
+                Some(action @ (Action::Help | Action::CommandPalette)) => {
+                    popups::open(action, Context::Info, app_state);
+                    return;
                 }
-                KeyCode::Char('g') => {
-                    state.scroll_offset = 0;
+                Some(action) => {
+                    if let Some(result) = state.info_action(action) {
+                        if let Err(err) = result {
+                            app_state.notify(format!("{err:#}"));
+                        }
+                        return;
+                    }
                 }
+                None => {}
+            }
+        }
+
+        let ActiveState::Read(reader) = &mut app_state.active_state else {
+            return;
+        };
+        let action = keymap::action(Context::Reader, event);
+        let scroll: Option<fn(&mut ReaderPageState)> = match action {
+            Some(Action::ScrollDown) => Some(ReaderPageState::scroll_down),
+            Some(Action::ScrollUp) => Some(ReaderPageState::scroll_up),
+            Some(Action::HalfPageDown) => Some(ReaderPageState::half_page_down),
+            Some(Action::HalfPageUp) => Some(ReaderPageState::half_page_up),
+            Some(Action::Bottom) => Some(ReaderPageState::scroll_to_bottom),
+            Some(Action::Top) => Some(ReaderPageState::scroll_to_top),
+            _ => None,
+        };
+        if let Some(scroll) = scroll {
+            reader.scroll(reader.focus_split, scroll);
+            return;
+        }
 
-                KeyCode::Char('N') if event.modifiers == KeyModifiers::SHIFT => {
-                    state.select_previous_search();
+        let state = reader.focused_mut();
+        match action {
+            Some(Action::PreviousMatch) => {
+                state.select_previous_search();
+            }
+            Some(Action::NextMatch) => {
+                state.select_next_search();
+            }
+            Some(Action::Search) => {
+                state.search_active = true;
+            }
+            Some(Action::Back) => {
+                if state.selection.is_some() {
+                    state.selection = None;
+                } else if !state.search.is_empty() {
+                    state.search = String::new();
+                    state.matches = Vec::new();
+                    state.selected_match = None;
+                } else if reader.split.is_some() {
+                    reader.close_split(width);
+                } else {
+                    Navigation::navigate_to(Navigation::List, app_state, ctx.controller);
                 }
-                KeyCode::Char('n') => {
-                    state.select_next_search();
+            }
+            Some(Action::SplitView) => {
+                let prompt = PagePrompt::new(PromptTarget::Split, state.page().cloned());
+                app_state.popup = Some(Popup::Page(prompt));
+            }
+            Some(Action::Export) => {
+                let popup = state.page().cloned().map(ExportPopup::new);
+                app_state.popup = popup.map(Popup::Export);
+            }
+            Some(Action::Diff) => {
+                let prompt = PagePrompt::new(PromptTarget::Diff, state.page().cloned());
+                app_state.popup = Some(Popup::Page(prompt));
+            }
+            Some(Action::FocusPane) => {
+                reader.focus_split = !reader.focus_split && reader.split.is_some();
+            }
+            Some(Action::SyncScroll) => {
+                reader.sync_scroll = !reader.sync_scroll;
+                let status = if reader.sync_scroll { "on" } else { "off" };
+                app_state.notify(format!("Synchronized scrolling {status}"));
+            }
+            Some(Action::Star) => {
+                let page = state.page().cloned();
+                app_state.toggle_favorite(page);
+            }
+            Some(Action::Collections) => {
+                let page = state.page().cloned();
+                popups::open_collections(page, app_state);
+            }
+            Some(Action::Tldr) => {
+                let toggled = state.toggle_tldr();
+                if !toggled {
+                    let name = state.page().map(|page| page.name.clone());
+                    let name = name.unwrap_or_default();
+                    app_state.notify(format!("No tldr page for {name}"));
                 }
-                KeyCode::Char('/') => {
-                    state.search_active = true;
+            }
+            Some(Action::Diagnostics) => {
+                let toggled = state.toggle_diagnostics();
+                if !toggled {
+                    app_state.notify("No warnings");
                 }
-                KeyCode::Backspace if state.search_active => {
-                    state.search.pop();
+            }
+            // Opening the info manual tells if there is none, even if the
+            // lookup for the hint is still running.
+            Some(Action::InfoManual) => match state.page().map(|page| page.name.clone()) {
+                Some(name) => {
+                    Navigation::navigate_to(Navigation::Info(name), app_state, ctx.controller);
                 }
-                KeyCode::Esc => {
-                    if state.search_active {
-                        state.search_active = false;
-                    } else if state.selection.is_some() {
-                        state.selection = None;
-                    } else if state.search.is_empty() {
-                        Navigation::navigate_to(&Navigation::List, app_state, ctx.controller);
-                    } else {
-                        state.search = String::new();
-                        state.matches = Vec::new();
-                        state.selected_match = None;
-                    }
+                None => app_state.notify("No info manual for this page"),
+            },
+            Some(Action::Edit) => {
+                let external = state
+                    .source()
+                    .and_then(|(path, read_only)| External::editor(&path, read_only));
+                match external {
+                    Ok(external) => app_state.run_external(external),
+                    Err(err) => app_state.notify(format!("{err:#}")),
                 }
-                KeyCode::Enter if state.search_active => {
-                    state.search_active = false;
+            }
+            Some(Action::Pager) => {
+                let name = state.title().unwrap_or_else(|| String::from("page"));
+                match External::pager(&state.manual, &name, state.current_line()) {
+                    Ok(external) => app_state.run_external(external),
+                    Err(err) => app_state.notify(format!("{err:#}")),
                 }
-                _ => {}
             }
-        };
+            Some(Action::CopySection) => match state.manual.section_text(state.current_line()) {
+                Some(text) => app_state.copy(&text),
+                None => app_state.notify("No section here"),
+            },
+            Some(Action::CopyExample) => match state.code_block() {
+                Some(block) => app_state.copy(&block.text),
+                None => app_state.notify("No example here"),
+            },
+            Some(Action::Examples) => {
+                let blocks = state.manual.code_blocks();
+                if blocks.is_empty() {
+                    app_state.notify("No examples found");
+                } else {
+                    let name = state.title().unwrap_or_else(|| String::from("page"));
+                    let popup = ExamplesPopup::new(&name, blocks);
+                    app_state.popup = Some(Popup::Examples(popup));
+                }
+            }
+            Some(Action::Annotate) => {
+                let popup = state.note_popup(state.current_line());
+                app_state.popup = popup.map(Popup::Note);
+            }
+            Some(action @ (Action::SetMark | Action::JumpToMark)) => {
+                state.jumps.pending = Some(action);
+            }
+            Some(Action::Marks) => match reader.marks_popup() {
+                Some(popup) => app_state.popup = Some(Popup::Marks(popup)),
+                None => app_state.notify("No marks on this page"),
+            },
+            Some(action) => popups::open(action, Context::Reader, app_state),
+            None => {}
+        }
     }
 }
 
@@ -255,58 +1391,183 @@ impl StatefulWidgetRef for ReaderPage {
         let theme = get_theme();
         buf.set_style(area, theme.base);
 
-        let [main, search] = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([Constraint::Min(0), Constraint::Length(1)])
-            .areas(area);
+        let focus_split = state.focus_split;
+        match state.split.as_deref_mut() {
+            Some(split) => {
+                let [left, right] = Layout::horizontal([Constraint::Fill(1); 2]).areas(area);
+                render_pane(
+                    &self.split_content,
+                    &self.split_search,
+                    right,
+                    buf,
+                    split,
+                    focus_split,
+                );
+                render_pane(&self.content, &self.search, left, buf, state, !focus_split);
+            }
+            None => render_pane(&self.content, &self.search, area, buf, state, true),
+        }
+    }
+}
 
-        let style = if state.search_active {
-            theme.block.inactive
-        } else {
-            theme.block.active
-        };
-
-        let block = Block::default()
-            .style(style)
-            .borders(Borders::ALL)
-            .border_type(ratatui::widgets::BorderType::Rounded)
-            .padding(Padding::horizontal(state.padding_x.saturating_sub(1)));
-        let inner = block.inner(main);
-        block.render(main, buf);
-
-        // Render the content.
-        self.content.render_ref(inner, buf, state);
-
-        // Render the search
-        self.search.render_ref(search, buf, state);
-
-        // Render the scrollbar.
-        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
-            .begin_symbol(Some("╮"))
-            .end_symbol(Some("╯"));
-
-        state.scrollbar = state.scrollbar.content_length(state.max_scroll_pos);
-        state.scrollbar = state.scrollbar.position(state.scroll_offset);
-        scrollbar.render(
-            main.inner(Margin {
-                horizontal: 0,
-                vertical: 0,
-            }),
-            buf,
-            &mut state.scrollbar,
-        );
+/// Renders the content, search bar and scrollbar of a pane.
+fn render_pane<C, S>(
+    content: &IStatefulWidget<C>,
+    search: &IStatefulWidget<S>,
+    area: Rect,
+    buf: &mut Buffer,
+    state: &mut ReaderPageState,
+    focused: bool,
+) where
+    IStatefulWidget<C>: StatefulWidgetRef<State = ReaderPageState>,
+    IStatefulWidget<S>: StatefulWidgetRef<State = ReaderPageState>,
+{
+    let theme = get_theme();
+
+    let [main, search_area] = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .areas(area);
+
+    // Render the warnings of a page file below the page.
+    let main = if state.show_diagnostics && !state.diagnostics.is_empty() {
+        let height = state.diagnostics.len().min(MAX_DIAGNOSTICS) as u16 + 2;
+        let [main, diagnostics] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(height)]).areas(main);
+        render_diagnostics(&state.diagnostics, diagnostics, buf);
+        main
+    } else {
+        main
+    };
+
+    let style = if state.search_active || !focused {
+        theme.block.inactive
+    } else {
+        theme.block.active
+    };
+
+    let mut block = Block::default()
+        .style(style)
+        .borders(Borders::ALL)
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .padding(Padding::horizontal(1));
+    let has_info = state.metadata.info.get().copied().unwrap_or(false);
+    if has_info && state.man_page.is_none() {
+        block = block.title_bottom(Line::from(" I: info manual ").right_aligned());
+    }
+    let inner = block.inner(main);
+    block.render(main, buf);
+
+    // Render the content.
+    content.render_ref(inner, buf, state);
+
+    // Render the search, and the status on the right.
+    let search_width = SEARCH_LABEL.chars().count() + state.search.chars().count() + 1;
+    let [search_area, status_area] =
+        Layout::horizontal([Constraint::Length(search_width as u16), Constraint::Fill(1)])
+            .areas(search_area);
+    search.render_ref(search_area, buf, state);
+    render_status(state, status_area, buf);
+
+    // Render the scrollbar.
+    let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(Some("╮"))
+        .end_symbol(Some("╯"));
+
+    state.scrollbar = state.scrollbar.content_length(state.max_scroll_pos);
+    state.scrollbar = state.scrollbar.position(state.scroll_offset);
+    scrollbar.render(
+        main.inner(Margin {
+            horizontal: 0,
+            vertical: 0,
+        }),
+        buf,
+        &mut state.scrollbar,
+    );
+    render_marks(state, main, buf);
+}
+
+/// Shows the letters of the marks on the scrollbar, where its thumb is when
+/// the mark is at the top.
+fn render_marks(state: &ReaderPageState, area: Rect, buf: &mut Buffer) {
+    let theme = get_theme();
+
+    // The track lies between the begin and end symbols.
+    // Without a scrollbar there is nothing to place the marks on.
+    let track = area.height.saturating_sub(2) as usize;
+    if track == 0 || state.max_scroll_pos == 0 || state.man_page.is_some() {
+        return;
+    }
+
+    let x = area.right().saturating_sub(1);
+    for (line, mark) in &state.jumps.marks {
+        let row = line.min(&state.max_scroll_pos) * (track - 1) / state.max_scroll_pos;
+        if let Some(cell) = buf.cell_mut((x, area.y + 1 + row as u16)) {
+            cell.set_char(mark.letter).set_style(theme.marker);
+        }
     }
 }
 
-pub(crate) struct Content;
+fn render_status(state: &mut ReaderPageState, area: Rect, buf: &mut Buffer) {
+    let theme = get_theme();
+
+    let fields = status_fields();
+    let text = state.status(&fields).line(&fields, area.width as usize);
+    Line::from(text)
+        .style(theme.search.inactive)
+        .right_aligned()
+        .render(area, buf);
+}
 
-impl EventfulWidget<AppState, Event> for Content {
+/// The number of warnings shown at once below a page file.
+const MAX_DIAGNOSTICS: usize = 5;
+
+fn render_diagnostics(diagnostics: &[Diagnostic], area: Rect, buf: &mut Buffer) {
+    let theme = get_theme();
+
+    let mut title = format!(" Diagnostics ({}) ", diagnostics.len());
+    if diagnostics.len() > MAX_DIAGNOSTICS {
+        title = format!(" Diagnostics ({MAX_DIAGNOSTICS} of {}) ", diagnostics.len());
+    }
+    let block = Block::default()
+        .title(title)
+        .style(theme.block.inactive)
+        .borders(Borders::ALL)
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .padding(Padding::horizontal(1));
+
+    let lines: Vec<Line> = diagnostics
+        .iter()
+        .take(MAX_DIAGNOSTICS)
+        .map(|diagnostic| Line::from(diagnostic.to_string()))
+        .collect();
+    Paragraph::new(lines).block(block).render(area, buf);
+}
+
+/// The text of a pane, `SPLIT` is set for the right pane of the split view.
+pub(crate) struct Content<const SPLIT: bool>;
+
+impl<const SPLIT: bool> EventfulWidget<AppState, Event> for Content<SPLIT> {
     fn unique_key() -> String {
-        String::from("ManPageContent")
+        if SPLIT {
+            String::from("ManPageSplitContent")
+        } else {
+            String::from("ManPageContent")
+        }
     }
 
     fn on_event(ctx: EventContext, app_state: &mut AppState, area: Option<Rect>) {
-        let ActiveState::Read(state) = &mut app_state.active_state else {
+        if app_state.popup.is_some() {
+            return;
+        }
+
+        let ActiveState::Read(reader) = &mut app_state.active_state else {
+            return;
+        };
+        // Both panes get every mouse event, but only the pane that was
+        // clicked in selects text.
+        let focused = reader.focus_split == SPLIT;
+        let Some(state) = reader.pane_mut(SPLIT) else {
             return;
         };
 
@@ -323,37 +1584,85 @@ impl EventfulWidget<AppState, Event> for Content {
                 state.padding_x,
                 state.padding_y,
             );
-            match e.kind {
-                MouseEventKind::ScrollUp => {
-                    if area.contains(position) {
-                        state.scroll_up();
+            // A click on a note marker in the gutter shows the note.
+            if let MouseEventKind::Down(_) = e.kind {
+                let row = position
+                    .y
+                    .checked_sub(area.y)
+                    .filter(|row| *row < area.height);
+                if let Some(row) = row.filter(|_| position.x + 1 == area.x) {
+                    let line = state.scroll_offset + row as usize;
+                    if state.notes.iter().any(|(l, _)| *l == line) {
+                        app_state.popup = state.note_popup(line).map(Popup::Note);
+                        return;
                     }
                 }
-                MouseEventKind::ScrollDown => {
-                    if area.contains(position) {
-                        state.scroll_down();
+
+                // A click on a reference of an info node follows it.
+                let column = position
+                    .x
+                    .checked_sub(area.x)
+                    .filter(|column| *column < area.width);
+                if let Some((row, column)) = row.zip(column) {
+                    let line = state.scroll_offset + row as usize;
+                    if let Some(result) = state.follow_reference_at(line, column as usize) {
+                        if let Err(err) = result {
+                            app_state.notify(format!("{err:#}"));
+                        }
+                        return;
                     }
                 }
-                MouseEventKind::Down(_) => {
-                    if area.contains(position) {
-                        state.search_active = false;
+            }
+
+            match e.kind {
+                MouseEventKind::ScrollUp if area.contains(position) => {
+                    reader.scroll(SPLIT, ReaderPageState::scroll_up);
+                }
+                MouseEventKind::ScrollDown if area.contains(position) => {
+                    reader.scroll(SPLIT, ReaderPageState::scroll_down);
+                }
+                MouseEventKind::Down(_) if area.contains(position) => {
+                    reader.focus_split = SPLIT;
+                    if let Some(other) = reader.pane_mut(!SPLIT) {
+                        other.selection = None;
+                        other.selection_active = false;
+                    }
+                    let state = reader.pane_mut(SPLIT).unwrap();
+                    state.search_active = false;
+                    state.cursor = Some(state.scroll_offset + (position.y - area.y) as usize);
 
-                        state.selection = Some(Selection::new(position_buffer, position_buffer));
-                        state.selection_active = false;
+                    // Shift extends the selection, double and triple clicks
+                    // select a word and a line.
+                    let clicks = state.count_clicks(position_buffer);
+                    let extend = e.modifiers.contains(KeyModifiers::SHIFT);
+                    match (&state.selection, clicks) {
+                        (Some(selection), _) if extend && state.selection_active => {
+                            state.selection =
+                                Some(Selection::new(selection.start, position_buffer));
+                        }
+                        (_, 2 | 3) => state.select_at(position_buffer, clicks == 3),
+                        _ => {
+                            state.selection =
+                                Some(Selection::new(position_buffer, position_buffer));
+                            state.selection_active = false;
+                        }
                     }
                 }
-                MouseEventKind::Drag(_) => {
+                MouseEventKind::Drag(_) if focused => {
                     state.selection_active = true;
                     if let Some(selection) = &state.selection {
                         state.selection = Some(Selection::new(selection.start, position_buffer));
                     }
                 }
-                MouseEventKind::Up(_) => {
+                MouseEventKind::Up(_) if focused => {
+                    // The copied selection stays highlighted until the next click.
                     if state.selection_active {
-                        state.copy_selection();
+                        if let Some(text) = state.selected_text() {
+                            app_state.copy(&text);
+                        }
+                    } else {
+                        state.selection = None;
                     }
-                    state.selection = None;
-                    state.selection_active = false;
                 }
                 _ => {}
             }
@@ -361,55 +1670,84 @@ impl EventfulWidget<AppState, Event> for Content {
     }
 }
 
-impl StatefulWidgetRef for Content {
+impl<const SPLIT: bool> StatefulWidgetRef for Content<SPLIT> {
     type State = ReaderPageState;
 
     fn render_ref(&self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
         let theme = get_theme();
 
         // Render the paragraph.
-        state.max_scroll_pos = state.text.height().saturating_sub(area.height as usize);
+        state.max_scroll_pos = state.num_lines.saturating_sub(area.height as usize);
         state.page_height = area.height as usize;
+        state.scroll_offset = state.scroll_offset.min(state.max_scroll_pos);
+        (state.padding_x, state.padding_y) = (area.x, area.y);
 
-        let style = if state.search_active {
-            theme.block.inactive
-        } else {
-            theme.block.active
-        };
+        let lines: Vec<Line> = state
+            .manual
+            .lines
+            .iter()
+            .skip(state.scroll_offset)
+            .take(area.height as usize)
+            .map(|line| {
+                let spans: Vec<Span> = line
+                    .iter()
+                    .map(|fragment| {
+                        let style = if state.search_active {
+                            theme.block.inactive
+                        } else {
+                            theme.base.patch(theme.man.get(fragment.emphasis))
+                        };
+                        Span::styled(fragment.text.clone(), style)
+                    })
+                    .collect();
+                Line::from(spans)
+            })
+            .collect();
 
-        let mut lines: Vec<Line> = Vec::new();
-        for line in state.text.lines.clone() {
-            let mut new_line: Vec<Span> = Vec::new();
-            for mut span in line {
-                if state.search_active {
-                    new_line.push(span.style(style));
-                } else {
-                    if let Some(bg) = theme.base.bg {
-                        let style = span.style;
-                        span = span.style(style.bg(bg));
-                    }
-                    new_line.push(span);
+        Paragraph::new(lines).render(area, buf);
+
+        // Mark the lines with notes in the gutter.
+        if area.x > 0 && state.man_page.is_none() {
+            for (line, _) in &state.notes {
+                let Some(row) = line.checked_sub(state.scroll_offset) else {
+                    continue;
+                };
+                if row >= area.height as usize {
+                    continue;
+                }
+                if let Some(cell) = buf.cell_mut((area.x - 1, area.y + row as u16)) {
+                    cell.set_symbol("▍").set_style(theme.marker);
                 }
             }
-            lines.push(Line::from(new_line));
         }
 
-        Paragraph::new(lines)
-            .scroll((state.scroll_offset as u16, 0))
-            .render(area, buf);
-
         // Highlight the search matches.
         let style = if state.search_active {
             theme.highlight.inactive
         } else {
             theme.highlight.active
         };
-        if let Some(selected_match) = state.selected_match() {
-            let x = selected_match.1 + state.padding_x;
-            let y = (selected_match.0 + state.padding_y).saturating_sub(state.scroll_offset as u16);
-            if y > 0 && y < area.height - 1 {
-                let area = Rect::new(x, y, state.search.len() as u16, 1);
-                Block::new().style(style).render(area, buf);
+        if let Some((row, column)) = state.selected_match() {
+            let row = (row as usize).checked_sub(state.scroll_offset);
+            if let Some(row) = row.filter(|row| *row < area.height as usize) {
+                let x = area.x + column;
+                let area = Rect::new(x, area.y + row as u16, state.search.len() as u16, 1);
+                Block::new()
+                    .style(style)
+                    .render(area.intersection(buf.area), buf);
+            }
+        }
+
+        // Highlight the selected reference of an info node.
+        if let Some(reference) = state.info().and_then(InfoView::selected) {
+            let row = reference.line.checked_sub(state.scroll_offset);
+            if let Some(row) = row.filter(|row| *row < area.height as usize) {
+                let x = area.x + reference.columns.start as u16;
+                let width = reference.columns.len() as u16;
+                let area = Rect::new(x, area.y + row as u16, width, 1);
+                Block::new()
+                    .style(theme.highlight.active)
+                    .render(area.intersection(buf.area), buf);
             }
         }
 
@@ -439,15 +1777,25 @@ impl StatefulWidgetRef for Content {
     }
 }
 
-struct Search;
+const SEARCH_LABEL: &str = " Search (/): ";
+
+struct Search<const SPLIT: bool>;
 
-impl EventfulWidget<AppState, Event> for Search {
+impl<const SPLIT: bool> EventfulWidget<AppState, Event> for Search<SPLIT> {
     fn unique_key() -> String {
-        String::from("ManPageSearch")
+        if SPLIT {
+            String::from("ManPageSplitSearch")
+        } else {
+            String::from("ManPageSearch")
+        }
     }
 
     fn on_event(ctx: EventContext, app_state: &mut AppState, area: Option<Rect>) {
-        let ActiveState::Read(state) = &mut app_state.active_state else {
+        if app_state.popup.is_some() {
+            return;
+        }
+
+        let ActiveState::Read(reader) = &mut app_state.active_state else {
             return;
         };
 
@@ -462,15 +1810,19 @@ impl EventfulWidget<AppState, Event> for Search {
             }
 
             if let MouseEventKind::Down(_) = e.kind {
+                let Some(state) = reader.pane_mut(SPLIT) else {
+                    return;
+                };
                 state.search_active = true;
                 state.selection = None;
                 state.selection_active = false;
+                reader.focus_split = SPLIT;
             }
         }
     }
 }
 
-impl StatefulWidgetRef for Search {
+impl<const SPLIT: bool> StatefulWidgetRef for Search<SPLIT> {
     type State = ReaderPageState;
     fn render_ref(&self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
         let theme = get_theme();
@@ -482,7 +1834,7 @@ impl StatefulWidgetRef for Search {
         };
 
         let mut spans = vec![
-            Span::styled(" Search (/): ", style),
+            Span::styled(SEARCH_LABEL, style),
             Span::styled(state.search.clone(), style),
         ];
         if state.search_active {
@@ -1,10 +1,7 @@
 #![allow(clippy::cast_possible_truncation)]
 use std::cmp::{max, min, Ordering};
 
-use ratatui::{
-    layout::{Constraint, Direction, Layout, Position, Rect},
-    text::Text,
-};
+use ratatui::layout::{Constraint, Direction, Layout, Position, Rect};
 
 pub(super) fn centered_rect(area: Rect, widget_height: u16) -> Rect {
     if area.height <= widget_height {
@@ -24,11 +21,10 @@ pub(super) fn centered_rect(area: Rect, widget_height: u16) -> Rect {
     area
 }
 
-pub(super) fn find_matches(text: &Text, query: &str) -> Vec<(u16, u16)> {
+pub(super) fn find_matches(lines: &[String], query: &str) -> Vec<(u16, u16)> {
     let mut positions = Vec::new();
 
-    for (current_row, line) in text.lines.clone().into_iter().enumerate() {
-        let line = line.to_string();
+    for (current_row, line) in lines.iter().enumerate() {
         for (index, _) in line.to_lowercase().match_indices(&query.to_lowercase()) {
             positions.push((current_row as u16, index as u16));
         }
@@ -192,13 +188,6 @@ fn is_after_or_equal(current: Position, other: Position) -> bool {
     current.y > other.y || (current.y == other.y && current.x >= other.x)
 }
 
-pub(super) fn text_to_lines(text: &Text) -> Vec<String> {
-    text.lines
-        .iter()
-        .map(|line| line.spans.iter().map(|x| x.content.to_string()).collect())
-        .collect()
-}
-
 pub(super) fn extract_text_from_lines(lines: &[String], selection: &Selection) -> String {
     let start_row = selection.start().0.y as usize;
     let start_col = selection.start().0.x as usize;
@@ -243,3 +232,83 @@ pub(super) fn extract_text_from_lines(lines: &[String], selection: &Selection) -
 
     extracted_text
 }
+
+/// Returns the first and last column of the word at a column. Words end at
+/// whitespace, such that flags like `--no-same-owner`, paths and references
+/// like `tar(1)` are one word, without surrounding quotes, brackets and
+/// punctuation.
+pub(super) fn word_at(line: &str, column: usize) -> Option<(usize, usize)> {
+    let chars: Vec<char> = line.chars().collect();
+    if chars.get(column).is_none_or(|ch| ch.is_whitespace()) {
+        return None;
+    }
+
+    let mut start = chars[..column]
+        .iter()
+        .rposition(|ch| ch.is_whitespace())
+        .map_or(0, |index| index + 1);
+    let mut end = chars[column..]
+        .iter()
+        .position(|ch| ch.is_whitespace())
+        .map_or(chars.len(), |index| column + index);
+
+    while start < end && "\"'`([{<".contains(chars[start]) {
+        start += 1;
+    }
+    while start < end {
+        let word = &chars[start..end];
+        let balanced = |open: char, close: char| {
+            let count = |c: char| word.iter().filter(|ch| **ch == c).count();
+            count(open) >= count(close)
+        };
+        let keep = match chars[end - 1] {
+            ')' => balanced('(', ')'),
+            ']' => balanced('[', ']'),
+            ch => !"\"'`}>.,;:!?".contains(ch),
+        };
+        if keep {
+            break;
+        }
+        end -= 1;
+    }
+
+    (start < end && (start..end).contains(&column)).then(|| (start, end - 1))
+}
+
+/// Returns the first and last column of the text of a line, without its indentation.
+pub(super) fn line_bounds(line: &str) -> Option<(usize, usize)> {
+    let chars: Vec<char> = line.chars().collect();
+    let start = chars.iter().position(|ch| !ch.is_whitespace())?;
+    let end = chars.iter().rposition(|ch| !ch.is_whitespace())?;
+    Some((start, end))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn word(line: &str, column: usize) -> Option<&str> {
+        let (start, end) = word_at(line, column)?;
+        line.get(start..=end)
+    }
+
+    #[test]
+    fn test_word_at() {
+        let line = "  --no-same-owner, see tar(1). Reads /etc/ssh/ssh_config";
+        assert_eq!(word(line, 5), Some("--no-same-owner"));
+        assert_eq!(word(line, 24), Some("tar(1)"));
+        assert_eq!(word(line, 45), Some("/etc/ssh/ssh_config"));
+        assert_eq!(word(line, 0), None);
+
+        assert_eq!(word("(see tar(1))", 6), Some("tar(1)"));
+        assert_eq!(word("[--color[=WHEN]]", 4), Some("--color[=WHEN]"));
+        assert_eq!(word("\"quoted\",", 0), None);
+        assert_eq!(word("\"quoted\",", 3), Some("quoted"));
+    }
+
+    #[test]
+    fn test_line_bounds() {
+        assert_eq!(line_bounds("       tar [OPTION...]  "), Some((7, 21)));
+        assert_eq!(line_bounds("   "), None);
+    }
+}
@@ -0,0 +1,203 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::prelude::*;
+use tui_widget_list::{ListBuilder, ListState, ListView};
+
+use super::{render_frame, Outcome};
+use crate::core::{Collections, PageRef};
+use crate::ui::theme::get_theme;
+
+/// Lists the collections, adds the current page to them and edits them.
+pub(crate) struct CollectionsPopup {
+    page: Option<PageRef>,
+    list: ListState,
+    input: Option<Input>,
+    error: Option<String>,
+    changed: bool,
+}
+
+/// A collection name that is being typed.
+enum Input {
+    New(String),
+    Rename {
+        old: String,
+        name: String,
+    },
+    /// Asks before a collection is deleted.
+    Delete(String),
+}
+
+impl CollectionsPopup {
+    pub(crate) fn new(page: Option<PageRef>, collections: &Collections) -> Self {
+        let mut list = ListState::default();
+        if collections.len() > 0 {
+            list.select(Some(0));
+        }
+
+        Self {
+            page,
+            list,
+            input: None,
+            error: None,
+            changed: false,
+        }
+    }
+
+    /// Returns whether the collections were edited since the last call.
+    pub(super) fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    fn selected_name(&self, collections: &Collections) -> Option<String> {
+        let collection = collections.get(self.list.selected?)?;
+        Some(collection.name.clone())
+    }
+
+    pub(super) fn on_key(&mut self, key: &KeyEvent, collections: &mut Collections) -> Outcome {
+        if let Some(input) = &mut self.input {
+            let name = match input {
+                Input::New(name) | Input::Rename { name, .. } => name,
+                Input::Delete(name) => {
+                    let name = name.clone();
+                    self.input = None;
+                    if matches!(key.code, KeyCode::Enter | KeyCode::Char('y')) {
+                        self.delete(&name, collections);
+                    }
+                    return Outcome::Keep;
+                }
+            };
+            match key.code {
+                KeyCode::Enter => self.submit(collections),
+                KeyCode::Esc => self.input = None,
+                KeyCode::Backspace => {
+                    name.pop();
+                }
+                KeyCode::Char(ch) => name.push(ch),
+                _ => {}
+            }
+            return Outcome::Keep;
+        }
+
+        self.error = None;
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.list.next(),
+            KeyCode::Char('k') | KeyCode::Up => self.list.previous(),
+            KeyCode::Char(' ') | KeyCode::Enter => {
+                let (Some(page), Some(name)) = (&self.page, self.selected_name(collections)) else {
+                    return Outcome::Keep;
+                };
+                collections.toggle(&name, page.clone());
+                self.changed = true;
+            }
+            KeyCode::Char('n') => self.input = Some(Input::New(String::new())),
+            KeyCode::Char('r') => {
+                if let Some(old) = self.selected_name(collections) {
+                    let name = old.clone();
+                    self.input = Some(Input::Rename { old, name });
+                }
+            }
+            KeyCode::Char('d') => {
+                if let Some(name) = self.selected_name(collections) {
+                    self.input = Some(Input::Delete(name));
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => return Outcome::Close,
+            _ => {}
+        }
+        Outcome::Keep
+    }
+
+    fn delete(&mut self, name: &str, collections: &mut Collections) {
+        collections.delete(name);
+        self.changed = true;
+
+        let last = collections.len().checked_sub(1);
+        let selected = self.list.selected.zip(last).map(|(s, l)| s.min(l));
+        self.list.select(selected);
+    }
+
+    fn submit(&mut self, collections: &mut Collections) {
+        let result = match &self.input {
+            Some(Input::New(name)) => collections.create(name).map(|()| {
+                // A new collection starts with the current page.
+                if let Some(page) = &self.page {
+                    collections.add(name.trim(), page.clone());
+                }
+                self.list.select(Some(collections.len() - 1));
+            }),
+            Some(Input::Rename { old, name }) => collections.rename(old, name),
+            Some(Input::Delete(_)) | None => return,
+        };
+
+        match result {
+            Ok(()) => {
+                self.input = None;
+                self.error = None;
+                self.changed = true;
+            }
+            Err(err) => self.error = Some(err.to_string()),
+        }
+    }
+
+    pub(super) fn render(&mut self, area: Rect, buf: &mut Buffer, collections: &Collections) {
+        let theme = get_theme();
+
+        let height = collections.len().max(1) as u16 + 4;
+        let title = match &self.page {
+            Some(page) => format!("Collections: {page}"),
+            None => String::from("Collections"),
+        };
+        let inner = render_frame(area, buf, &title, 48, height);
+
+        let [list, _, message] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .areas(inner);
+
+        if collections.len() == 0 {
+            Line::from(" No collections yet")
+                .style(theme.list.inactive)
+                .render(list, buf);
+        }
+
+        let builder = ListBuilder::new(|context| {
+            let collection = collections.get(context.index).unwrap();
+            let marker = match &self.page {
+                Some(page) if collection.pages.contains(page) => "[x] ",
+                Some(_) => "[ ] ",
+                None => "",
+            };
+            let text = format!(" {marker}{} ({})", collection.name, collection.pages.len());
+
+            let mut line = Line::from(text).style(theme.list.active);
+            if context.is_selected {
+                line = line.style(theme.list.selected);
+            }
+            (line, 1)
+        });
+
+        ListView::new(builder, collections.len())
+            .infinite_scrolling(false)
+            .render(list, buf, &mut self.list);
+
+        let line = match (&self.input, &self.error) {
+            (_, Some(err)) => Line::from(format!(" {err}")).style(theme.highlight.active),
+            (Some(Input::Delete(name)), None) => {
+                Line::from(format!(" Delete {name}? y to confirm")).style(theme.highlight.active)
+            }
+            (Some(Input::New(name) | Input::Rename { name, .. }), None) => Line::from(vec![
+                Span::styled(" Name: ", theme.search.active),
+                Span::styled(name.clone(), theme.search.active),
+                Span::styled(" ", theme.search.active.reversed()),
+            ]),
+            (None, None) if self.page.is_some() => {
+                Line::from(" Space toggle, n new, r rename, d delete").style(theme.list.inactive)
+            }
+            (None, None) => Line::from(" n new, r rename, d delete").style(theme.list.inactive),
+        };
+        line.render(message, buf);
+    }
+}
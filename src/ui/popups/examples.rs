@@ -0,0 +1,103 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Paragraph};
+use tui_widget_list::{ListBuilder, ListState, ListView};
+
+use super::{render_frame, Outcome};
+use crate::core::CodeBlock;
+use crate::ui::theme::get_theme;
+
+/// Lists the examples and code blocks of a page to copy one of them.
+pub(crate) struct ExamplesPopup {
+    page: String,
+    blocks: Vec<CodeBlock>,
+    list: ListState,
+}
+
+impl ExamplesPopup {
+    pub(crate) fn new(page: &str, blocks: Vec<CodeBlock>) -> Self {
+        let mut list = ListState::default();
+        if !blocks.is_empty() {
+            list.select(Some(0));
+        }
+
+        Self {
+            page: page.to_string(),
+            blocks,
+            list,
+        }
+    }
+
+    pub(super) fn on_key(&mut self, key: &KeyEvent) -> Outcome {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.list.next(),
+            KeyCode::Char('k') | KeyCode::Up => self.list.previous(),
+            KeyCode::Enter => {
+                if let Some(block) = self.list.selected.and_then(|i| self.blocks.get(i)) {
+                    return Outcome::Copy(block.text.clone());
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q' | 'x') => return Outcome::Close,
+            _ => {}
+        }
+        Outcome::Keep
+    }
+
+    pub(super) fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let theme = get_theme();
+
+        let title = format!("Examples in {}", self.page);
+        let inner = render_frame(area, buf, &title, 80, 24);
+        let [list, preview, message] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+                Constraint::Length(1),
+            ])
+            .areas(inner);
+
+        if self.blocks.is_empty() {
+            Line::from(" No examples found")
+                .style(theme.list.inactive)
+                .render(list, buf);
+        }
+
+        let builder = ListBuilder::new(|context| {
+            let block = &self.blocks[context.index];
+            let first = block.text.lines().next().unwrap_or_default();
+            let more = if block.text.contains('\n') {
+                " …"
+            } else {
+                ""
+            };
+            let text = format!(" {:<16} {first}{more}", block.heading);
+
+            let mut line = Line::from(text).style(theme.list.active);
+            if context.is_selected {
+                line = line.style(theme.list.selected);
+            }
+            (line, 1)
+        });
+
+        ListView::new(builder, self.blocks.len())
+            .infinite_scrolling(false)
+            .render(list, buf, &mut self.list);
+
+        // Show the whole selected block.
+        if let Some(block) = self.list.selected.and_then(|i| self.blocks.get(i)) {
+            Paragraph::new(block.text.as_str())
+                .style(theme.base)
+                .block(
+                    Block::new()
+                        .borders(Borders::TOP)
+                        .border_style(theme.block.inactive),
+                )
+                .render(preview, buf);
+        }
+
+        Line::from(" Enter to copy, Esc to close")
+            .style(theme.list.inactive)
+            .render(message, buf);
+    }
+}
@@ -0,0 +1,104 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::prelude::*;
+use std::fs;
+
+use super::{render_frame, Outcome};
+use crate::core::{export_page, Format, PageRef};
+use crate::ui::theme::get_theme;
+
+/// Exports the displayed page to a file.
+pub(crate) struct ExportPopup {
+    page: PageRef,
+    format: Format,
+    path: String,
+    error: Option<String>,
+}
+
+impl ExportPopup {
+    pub(crate) fn new(page: PageRef) -> Self {
+        let format = Format::Markdown;
+        Self {
+            path: format.file_name(&page),
+            page,
+            format,
+            error: None,
+        }
+    }
+
+    pub(super) fn on_key(&mut self, key: &KeyEvent) -> Outcome {
+        match key.code {
+            KeyCode::Enter => match self.export() {
+                Ok(()) => {
+                    return Outcome::Notify(format!("Exported {} to {}", self.page, self.path));
+                }
+                Err(err) => self.error = Some(format!("{err:#}")),
+            },
+            KeyCode::Tab => self.next_format(),
+            KeyCode::Esc => return Outcome::Close,
+            KeyCode::Backspace => {
+                self.path.pop();
+            }
+            KeyCode::Char(ch) => self.path.push(ch),
+            _ => {}
+        }
+        Outcome::Keep
+    }
+
+    fn export(&self) -> anyhow::Result<()> {
+        let (_, text) = export_page(&self.page, self.format)?;
+        fs::write(self.path.trim(), text)?;
+        Ok(())
+    }
+
+    /// Selects the next format and updates the extension of the file.
+    fn next_format(&mut self) {
+        let index = Format::ALL.iter().position(|f| *f == self.format);
+        let next = Format::ALL[index.map_or(0, |i| (i + 1) % Format::ALL.len())];
+
+        let extension = format!(".{}", self.format.extension());
+        if let Some(stem) = self.path.strip_suffix(&extension) {
+            self.path = format!("{stem}.{}", next.extension());
+        }
+        self.format = next;
+    }
+
+    pub(super) fn render(&self, area: Rect, buf: &mut Buffer) {
+        let theme = get_theme();
+
+        let title = format!("Export {}", self.page);
+        let inner = render_frame(area, buf, &title, 56, 6);
+        let [format, path, hint] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(2),
+                Constraint::Length(1),
+            ])
+            .areas(inner);
+
+        let mut spans = vec![Span::styled(" Format: ", theme.base)];
+        for format in Format::ALL {
+            let style = if format == self.format {
+                theme.list.selected
+            } else {
+                theme.list.inactive
+            };
+            spans.push(Span::styled(format!(" {format} "), style));
+        }
+        Line::from(spans).render(format, buf);
+
+        Line::from(vec![
+            Span::styled(format!(" File:   {}", self.path), theme.base),
+            Span::styled(" ", theme.base.reversed()),
+        ])
+        .render(path, buf);
+
+        let hint_text = match &self.error {
+            Some(error) => format!(" {error}"),
+            None => String::from(" Tab to change the format, Enter to export"),
+        };
+        Line::from(hint_text)
+            .style(theme.list.inactive)
+            .render(hint, buf);
+    }
+}
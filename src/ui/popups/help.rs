@@ -0,0 +1,75 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::prelude::*;
+use ratatui::widgets::Paragraph;
+
+use super::{render_frame, Outcome};
+use crate::ui::keymap::{self, bindings_by_category, format_keys, Context};
+use crate::ui::theme::get_theme;
+
+/// Lists the key bindings that apply on the current page.
+pub(crate) struct HelpPopup {
+    contexts: Vec<Context>,
+    scroll_offset: u16,
+    max_scroll_pos: u16,
+}
+
+impl HelpPopup {
+    pub(crate) fn new(context: Context) -> Self {
+        Self {
+            contexts: keymap::contexts(context),
+            scroll_offset: 0,
+            max_scroll_pos: 0,
+        }
+    }
+
+    pub(super) fn on_key(&mut self, key: &KeyEvent) -> Outcome {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.scroll_offset = (self.scroll_offset + 1).min(self.max_scroll_pos);
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.scroll_offset = self.scroll_offset.saturating_sub(1);
+            }
+            KeyCode::Esc | KeyCode::Char('?' | 'q') => return Outcome::Close,
+            _ => {}
+        }
+        Outcome::Keep
+    }
+
+    pub(super) fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let theme = get_theme();
+
+        let groups = bindings_by_category(&self.contexts);
+        let keys_width = groups
+            .iter()
+            .flat_map(|(_, bindings)| bindings.iter().map(|b| format_keys(b).chars().count()))
+            .max()
+            .unwrap_or_default();
+
+        let mut lines = Vec::new();
+        for (category, bindings) in groups {
+            if !lines.is_empty() {
+                lines.push(Line::default());
+            }
+            lines.push(Line::from(format!(" {}", category.title())).style(theme.list.selected));
+            for binding in bindings {
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!(" {:<keys_width$}  ", format_keys(binding)),
+                        theme.base.bold(),
+                    ),
+                    Span::styled(binding.description, theme.base),
+                ]));
+            }
+        }
+
+        let height = lines.len() as u16 + 2;
+        let title = format!("Key bindings: {}", self.contexts[0].title());
+        let inner = render_frame(area, buf, &title, 64, height);
+
+        self.max_scroll_pos = (lines.len() as u16).saturating_sub(inner.height);
+        Paragraph::new(lines)
+            .scroll((self.scroll_offset, 0))
+            .render(inner, buf);
+    }
+}
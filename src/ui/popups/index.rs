@@ -0,0 +1,117 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::prelude::*;
+use tui_widget_list::{ListBuilder, ListState, ListView};
+
+use super::{render_frame, Outcome};
+use crate::core::IndexEntry;
+use crate::ui::theme::get_theme;
+
+/// Searches the index of an info manual and goes to the node of an entry.
+pub(crate) struct IndexPopup {
+    manual: String,
+    entries: Vec<IndexEntry>,
+    input: String,
+    /// The indices of the entries that contain the input.
+    matches: Vec<usize>,
+    list: ListState,
+}
+
+impl IndexPopup {
+    pub(crate) fn new(manual: &str, entries: Vec<IndexEntry>) -> Self {
+        let mut popup = Self {
+            manual: manual.to_string(),
+            entries,
+            input: String::new(),
+            matches: Vec::new(),
+            list: ListState::default(),
+        };
+        popup.filter();
+        popup
+    }
+
+    pub(super) fn on_key(&mut self, key: &KeyEvent) -> Outcome {
+        match key.code {
+            KeyCode::Down => self.list.next(),
+            KeyCode::Up => self.list.previous(),
+            KeyCode::Enter => {
+                let selected = self.list.selected.and_then(|i| self.matches.get(i));
+                if let Some(entry) = selected.map(|index| &self.entries[*index]) {
+                    return Outcome::InfoNode(entry.node.clone(), entry.line);
+                }
+            }
+            KeyCode::Esc => return Outcome::Close,
+            KeyCode::Backspace => {
+                self.input.pop();
+                self.filter();
+            }
+            KeyCode::Char(ch) => {
+                self.input.push(ch);
+                self.filter();
+            }
+            _ => {}
+        }
+        Outcome::Keep
+    }
+
+    fn filter(&mut self) {
+        let input = self.input.to_lowercase();
+        self.matches = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.entry.to_lowercase().contains(&input))
+            .map(|(index, _)| index)
+            .collect();
+        self.list = ListState::default();
+        if !self.matches.is_empty() {
+            self.list.select(Some(0));
+        }
+    }
+
+    pub(super) fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let theme = get_theme();
+
+        let title = format!("Index of {}", self.manual);
+        let inner = render_frame(area, buf, &title, 72, 24);
+        let [input, list, _, message] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Min(0),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .areas(inner);
+
+        Line::from(vec![
+            Span::styled(format!(" Search: {}", self.input), theme.base),
+            Span::styled(" ", theme.base.reversed()),
+        ])
+        .render(input, buf);
+
+        if self.matches.is_empty() {
+            Line::from(" No matching entries")
+                .style(theme.list.inactive)
+                .render(list, buf);
+        }
+
+        let builder = ListBuilder::new(|context| {
+            let entry = &self.entries[self.matches[context.index]];
+            let text = format!(" {:<40} {}", entry.entry, entry.node);
+
+            let mut line = Line::from(text).style(theme.list.active);
+            if context.is_selected {
+                line = line.style(theme.list.selected);
+            }
+            (line, 1)
+        });
+
+        ListView::new(builder, self.matches.len())
+            .infinite_scrolling(false)
+            .render(list, buf, &mut self.list);
+
+        Line::from(" Enter to go to the entry, Esc to close")
+            .style(theme.list.inactive)
+            .render(message, buf);
+    }
+}
@@ -0,0 +1,87 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::prelude::*;
+use tui_widget_list::{ListBuilder, ListState, ListView};
+
+use super::{render_frame, Outcome};
+use crate::core::Mark;
+use crate::ui::theme::get_theme;
+
+/// Lists the marks of a page to jump to one of them.
+pub(crate) struct MarksPopup {
+    page: String,
+    /// The marks and the lines they point to.
+    marks: Vec<(usize, Mark)>,
+    list: ListState,
+}
+
+impl MarksPopup {
+    pub(crate) fn new(page: &str, marks: Vec<(usize, Mark)>) -> Self {
+        let mut list = ListState::default();
+        if !marks.is_empty() {
+            list.select(Some(0));
+        }
+
+        Self {
+            page: page.to_string(),
+            marks,
+            list,
+        }
+    }
+
+    pub(super) fn on_key(&mut self, key: &KeyEvent) -> Outcome {
+        match key.code {
+            KeyCode::Down => self.list.next(),
+            KeyCode::Up => self.list.previous(),
+            KeyCode::Enter => {
+                if let Some((line, _)) = self.list.selected.and_then(|i| self.marks.get(i)) {
+                    return Outcome::ScrollTo(*line);
+                }
+            }
+            // Like `'` in the reader, a letter jumps to its mark.
+            KeyCode::Char(letter) => {
+                if let Some((line, _)) = self.marks.iter().find(|(_, m)| m.letter == letter) {
+                    return Outcome::ScrollTo(*line);
+                }
+            }
+            KeyCode::Esc => return Outcome::Close,
+            _ => {}
+        }
+        Outcome::Keep
+    }
+
+    pub(super) fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let theme = get_theme();
+
+        let title = format!("Marks in {}", self.page);
+        let inner = render_frame(area, buf, &title, 64, 16);
+        let [list, message] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Fill(1), Constraint::Length(1)])
+            .areas(inner);
+
+        let builder = ListBuilder::new(|context| {
+            let (line, mark) = &self.marks[context.index];
+            let text = format!(
+                " {}  {:<16.16} {:<32.32} {:>5}",
+                mark.letter,
+                mark.anchor.heading,
+                mark.anchor.text,
+                line + 1
+            );
+
+            let mut line = Line::from(text).style(theme.list.active);
+            if context.is_selected {
+                line = line.style(theme.list.selected);
+            }
+            (line, 1)
+        });
+
+        ListView::new(builder, self.marks.len())
+            .infinite_scrolling(false)
+            .render(list, buf, &mut self.list);
+
+        Line::from(" Enter or a letter to jump, Esc to close")
+            .style(theme.list.inactive)
+            .render(message, buf);
+    }
+}
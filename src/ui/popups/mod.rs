@@ -0,0 +1,252 @@
+use ratatui::crossterm::event::KeyEvent;
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, BorderType, Borders, Clear};
+use tachyonfx::CenteredShrink;
+
+use super::app::{ActiveState, AppState, Navigation};
+use super::events::{Event, EventContext};
+use super::keymap::{Action, Context};
+use super::theme::get_theme;
+use crate::core::{Collections, History, PageRef, Source};
+
+pub(super) mod collections;
+pub(super) mod examples;
+pub(super) mod export;
+pub(super) mod help;
+pub(super) mod index;
+pub(super) mod marks;
+pub(super) mod note;
+pub(super) mod palette;
+pub(super) mod prompt;
+pub(super) mod recent;
+pub(super) mod resume;
+pub(super) mod theme;
+
+pub(crate) use collections::CollectionsPopup;
+pub(crate) use examples::ExamplesPopup;
+pub(crate) use export::ExportPopup;
+pub(crate) use help::HelpPopup;
+pub(crate) use index::IndexPopup;
+pub(crate) use marks::MarksPopup;
+pub(crate) use note::NotePopup;
+pub(crate) use palette::CommandPalette;
+pub(crate) use prompt::{PagePrompt, PromptTarget};
+pub(crate) use recent::RecentPopup;
+pub(crate) use resume::ResumePrompt;
+pub(crate) use theme::ThemePicker;
+
+/// A modal popup drawn on top of the active page.
+///
+/// While a popup is open it receives all key events, the pages ignore them.
+pub(crate) enum Popup {
+    Help(HelpPopup),
+    Theme(ThemePicker),
+    Collections(CollectionsPopup),
+    Recent(RecentPopup),
+    Resume(ResumePrompt),
+    Note(NotePopup),
+    Page(PagePrompt),
+    Export(ExportPopup),
+    Index(IndexPopup),
+    Palette(CommandPalette),
+    Examples(ExamplesPopup),
+    Marks(MarksPopup),
+}
+
+/// Whether a popup stays open after handling an event.
+pub(crate) enum Outcome {
+    Keep,
+    Close,
+    /// Closes the popup and opens a page in the reader.
+    Open(PageRef),
+    /// Closes the popup and scrolls the reader to a line.
+    ScrollTo(usize),
+    /// Closes the popup and opens a page next to the one in the reader.
+    OpenSplit(PageRef),
+    /// Closes the popup and compares the page in the reader with another one.
+    Diff(Source),
+    /// Closes the popup and shows a message.
+    Notify(String),
+    /// Closes the popup and goes to a node of the info manual in the reader, at a line.
+    InfoNode(String, Option<usize>),
+    /// Closes the popup and handles a key as if it was pressed.
+    Run(KeyEvent),
+    /// Closes the popup and copies text to the clipboard.
+    Copy(String),
+}
+
+/// Opens the popup of a global action.
+pub(crate) fn open(action: Action, context: Context, state: &mut AppState) {
+    state.popup = match action {
+        Action::Help => Some(Popup::Help(HelpPopup::new(context))),
+        Action::ChangeTheme => Some(Popup::Theme(ThemePicker::new(&state.theme))),
+        Action::Recent => Some(Popup::Recent(RecentPopup::new(&state.history))),
+        Action::CommandPalette => Some(Popup::Palette(palette(context, state))),
+        _ => return,
+    };
+}
+
+/// Creates the command palette, which completes the pages of the list
+/// and the headings of the page in the reader.
+fn palette(context: Context, state: &AppState) -> CommandPalette {
+    let commands = state.list_commands().unwrap_or_default();
+    let mut pages: Vec<String> = commands
+        .iter()
+        .map(|command| {
+            command
+                .split_once(" (")
+                .map_or(command.as_str(), |(name, _)| name)
+        })
+        .map(str::to_string)
+        .collect();
+    pages.dedup();
+
+    let headings = match &state.active_state {
+        ActiveState::Read(reader) => Some(reader.headings().to_vec()),
+        _ => None,
+    };
+    CommandPalette::new(context, state.command_history.clone(), pages, headings)
+}
+
+/// Opens the collections popup for a page.
+pub(crate) fn open_collections(page: Option<PageRef>, state: &mut AppState) {
+    let popup = CollectionsPopup::new(page, &state.collections);
+    state.popup = Some(Popup::Collections(popup));
+}
+
+/// Forwards an event to the open popup. Returns `false` if no popup is open.
+pub(crate) fn on_event(ctx: &EventContext, state: &mut AppState) -> bool {
+    let Some(popup) = &mut state.popup else {
+        return false;
+    };
+
+    let Event::Key(key) = ctx.event else {
+        return true;
+    };
+
+    let outcome = match popup {
+        Popup::Help(help) => help.on_key(key),
+        Popup::Theme(picker) => picker.on_key(key, &mut state.theme),
+        Popup::Collections(popup) => {
+            let outcome = popup.on_key(key, &mut state.collections);
+            if popup.take_changed() {
+                state.save_collections();
+            }
+            outcome
+        }
+        Popup::Recent(recent) => recent.on_key(key, &state.history),
+        Popup::Resume(prompt) => prompt.on_key(key),
+        Popup::Note(popup) => {
+            let outcome = popup.on_key(key, &mut state.notes);
+            if popup.take_changed() {
+                state.save_notes();
+            }
+            outcome
+        }
+        Popup::Page(prompt) => prompt.on_key(key),
+        Popup::Export(popup) => popup.on_key(key),
+        Popup::Index(popup) => popup.on_key(key),
+        Popup::Palette(palette) => {
+            let outcome = palette.on_key(key);
+            if let Some(command) = palette.take_ran() {
+                state.command_history.retain(|past| *past != command);
+                state.command_history.push(command);
+            }
+            outcome
+        }
+        Popup::Examples(popup) => popup.on_key(key),
+        Popup::Marks(popup) => popup.on_key(key),
+    };
+
+    match outcome {
+        Outcome::Keep => {}
+        Outcome::Close => state.popup = None,
+        Outcome::Open(page) => {
+            state.popup = None;
+            Navigation::navigate_to(Navigation::Resume(page), state, ctx.controller);
+        }
+        Outcome::ScrollTo(line) => {
+            state.popup = None;
+            if let ActiveState::Read(reader) = &mut state.active_state {
+                reader.scroll_to(line);
+            }
+        }
+        Outcome::OpenSplit(page) => {
+            state.popup = None;
+            state.open_split(&page);
+        }
+        Outcome::Diff(source) => {
+            state.popup = None;
+            state.open_diff(source, ctx.controller);
+        }
+        Outcome::Notify(message) => {
+            state.popup = None;
+            state.notify(message);
+        }
+        Outcome::InfoNode(node, line) => {
+            state.popup = None;
+            if let ActiveState::Read(reader) = &mut state.active_state {
+                if let Err(err) = reader.go_to_info_node(&node, line) {
+                    state.notify(format!("{err:#}"));
+                }
+            }
+        }
+        Outcome::Run(key) => {
+            state.popup = None;
+            let _ = state.sx.send(Event::Key(key));
+        }
+        Outcome::Copy(text) => {
+            state.popup = None;
+            state.copy(&text);
+        }
+    }
+
+    true
+}
+
+/// Draws the open popup. Some popups show parts of the app state.
+pub(crate) struct PopupView<'a> {
+    pub(crate) collections: &'a Collections,
+    pub(crate) history: &'a History,
+}
+
+impl StatefulWidget for PopupView<'_> {
+    type State = Popup;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        match state {
+            Popup::Help(help) => help.render(area, buf),
+            Popup::Theme(picker) => picker.render(area, buf),
+            Popup::Collections(popup) => popup.render(area, buf, self.collections),
+            Popup::Recent(recent) => recent.render(area, buf, self.history),
+            Popup::Resume(prompt) => prompt.render(area, buf),
+            Popup::Note(popup) => popup.render(area, buf),
+            Popup::Page(prompt) => prompt.render(area, buf),
+            Popup::Export(popup) => popup.render(area, buf),
+            Popup::Index(popup) => popup.render(area, buf),
+            Popup::Palette(palette) => palette.render(area, buf),
+            Popup::Examples(popup) => popup.render(area, buf),
+            Popup::Marks(popup) => popup.render(area, buf),
+        }
+    }
+}
+
+/// Clears a centered area of the given size and draws a titled border around it.
+/// Returns the inner area.
+fn render_frame(area: Rect, buf: &mut Buffer, title: &str, width: u16, height: u16) -> Rect {
+    let theme = get_theme();
+
+    let area = area.inner_centered(width.min(area.width), height.min(area.height));
+    Clear.render(area, buf);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(format!(" {title} "))
+        .style(theme.base)
+        .border_style(theme.block.active);
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    inner
+}
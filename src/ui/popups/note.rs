@@ -0,0 +1,85 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::prelude::*;
+use ratatui::widgets::{Paragraph, Wrap};
+
+use super::{render_frame, Outcome};
+use crate::core::{Anchor, Notes, PageRef};
+use crate::ui::theme::get_theme;
+
+/// Shows and edits the note on a line of a page.
+pub(crate) struct NotePopup {
+    page: PageRef,
+    anchor: Anchor,
+    text: String,
+    changed: bool,
+}
+
+impl NotePopup {
+    pub(crate) fn new(page: PageRef, anchor: Anchor, text: String) -> Self {
+        Self {
+            page,
+            anchor,
+            text,
+            changed: false,
+        }
+    }
+
+    /// Returns whether the note was saved since the last call.
+    pub(super) fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    pub(super) fn on_key(&mut self, key: &KeyEvent, notes: &mut Notes) -> Outcome {
+        match key.code {
+            KeyCode::Enter => {
+                notes.set(&self.page, &self.anchor, &self.text);
+                self.changed = true;
+                return Outcome::Close;
+            }
+            KeyCode::Esc => return Outcome::Close,
+            KeyCode::Backspace => {
+                self.text.pop();
+            }
+            KeyCode::Char(ch) => self.text.push(ch),
+            _ => {}
+        }
+        Outcome::Keep
+    }
+
+    pub(super) fn render(&self, area: Rect, buf: &mut Buffer) {
+        let theme = get_theme();
+
+        let title = format!("Note: {}", self.page);
+        let inner = render_frame(area, buf, &title, 64, 10);
+
+        let [line, text, hint] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Min(0),
+                Constraint::Length(1),
+            ])
+            .areas(inner);
+
+        let place = match (self.anchor.heading.as_str(), self.anchor.text.as_str()) {
+            ("", text) => text.to_string(),
+            (heading, "") => heading.to_string(),
+            (heading, text) => format!("{heading} › {text}"),
+        };
+        Line::from(format!(" {place}"))
+            .style(theme.list.inactive)
+            .render(line, buf);
+
+        let spans = vec![
+            Span::styled(format!(" {}", self.text), theme.base),
+            Span::styled(" ", theme.base.reversed()),
+        ];
+        Paragraph::new(Line::from(spans))
+            .wrap(Wrap { trim: false })
+            .render(text, buf);
+
+        Line::from(" Enter to save, Esc to cancel, empty to delete")
+            .style(theme.list.inactive)
+            .render(hint, buf);
+    }
+}
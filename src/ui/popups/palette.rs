@@ -0,0 +1,452 @@
+use anyhow::{anyhow, bail, Result};
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::prelude::*;
+use tui_widget_list::{ListBuilder, ListState, ListView};
+
+use super::{render_frame, Outcome};
+use crate::core::PageRef;
+use crate::ui::keymap::{self, format_keys, Action, Binding, Context, BINDINGS};
+use crate::ui::theme::get_theme;
+
+/// A command that takes arguments, e.g. `open ssh_config 5`.
+struct Command {
+    name: &'static str,
+    usage: &'static str,
+    description: &'static str,
+    /// Whether the command needs a page in the reader.
+    reader: bool,
+}
+
+const COMMANDS: &[Command] = &[
+    Command {
+        name: "open",
+        usage: "open <page> [section]",
+        description: "Open a man page",
+        reader: false,
+    },
+    Command {
+        name: "goto",
+        usage: "goto <line | heading>",
+        description: "Go to a line or a section of the page",
+        reader: true,
+    },
+];
+
+/// What the palette lists: the commands and the actions of the key bindings.
+#[derive(Clone, Copy)]
+enum Entry {
+    Command(&'static Command),
+    Binding(&'static Binding),
+}
+
+impl Entry {
+    fn text(self) -> &'static str {
+        match self {
+            Entry::Command(command) => command.description,
+            Entry::Binding(binding) => binding.description,
+        }
+    }
+
+    /// The text matched against the input, commands also match by name.
+    fn matches(self, input: &str) -> Option<usize> {
+        match self {
+            Entry::Command(command) => fuzzy_match(input, command.name)
+                .into_iter()
+                .chain(fuzzy_match(input, command.description))
+                .min(),
+            Entry::Binding(binding) => fuzzy_match(input, binding.description),
+        }
+    }
+}
+
+/// Runs the action of a key binding, or a command with arguments, found
+/// by fuzzy matching. Tab completes arguments, ↑ and ↓ recall past commands.
+pub(crate) struct CommandPalette {
+    entries: Vec<Entry>,
+    input: String,
+    /// The indices of the entries that match the input, best match first.
+    matches: Vec<usize>,
+    list: ListState,
+    /// The names of the pages in the list, completed by `open`.
+    pages: Vec<String>,
+    /// The headings of the page in the reader with their lines, completed by `goto`.
+    headings: Option<Vec<(usize, String)>>,
+    /// The past commands, most recent last, and the recalled one.
+    history: Vec<String>,
+    recalled: Option<usize>,
+    /// The input before the first Tab and the completion shown.
+    completion: Option<(String, usize)>,
+    /// A command that ran and is yet to be added to the history.
+    ran: Option<String>,
+    error: Option<String>,
+}
+
+impl CommandPalette {
+    pub(crate) fn new(
+        context: Context,
+        history: Vec<String>,
+        pages: Vec<String>,
+        headings: Option<Vec<(usize, String)>>,
+    ) -> Self {
+        let contexts = keymap::contexts(context);
+        let commands = COMMANDS
+            .iter()
+            .filter(|command| !command.reader || headings.is_some())
+            .map(Entry::Command);
+        let bindings = contexts
+            .iter()
+            .filter(|context| **context != Context::Search)
+            .flat_map(|context| BINDINGS.iter().filter(|b| b.context == *context))
+            .filter(|binding| binding.action != Action::CommandPalette)
+            .filter(|binding| key_event(binding).is_some())
+            .map(Entry::Binding);
+
+        let mut palette = Self {
+            entries: commands.chain(bindings).collect(),
+            input: String::new(),
+            matches: Vec::new(),
+            list: ListState::default(),
+            pages,
+            headings,
+            history,
+            recalled: None,
+            completion: None,
+            ran: None,
+            error: None,
+        };
+        palette.filter();
+        palette
+    }
+
+    pub(super) fn on_key(&mut self, key: &KeyEvent) -> Outcome {
+        if !matches!(key.code, KeyCode::Tab | KeyCode::BackTab) {
+            self.completion = None;
+        }
+
+        match key.code {
+            KeyCode::Enter => return self.run(),
+            KeyCode::Esc => return Outcome::Close,
+            KeyCode::Tab | KeyCode::BackTab => {
+                let forward = key.code == KeyCode::Tab;
+                if self.command().is_some() {
+                    self.complete(forward);
+                } else if forward {
+                    self.list.next();
+                } else {
+                    self.list.previous();
+                }
+            }
+            KeyCode::Up => self.recall(true),
+            KeyCode::Down => self.recall(false),
+            KeyCode::Backspace => {
+                self.input.pop();
+                self.filter();
+            }
+            KeyCode::Char(ch) => {
+                self.input.push(ch);
+                self.filter();
+            }
+            _ => {}
+        }
+        Outcome::Keep
+    }
+
+    /// Returns a command that ran, to be remembered for the next palette.
+    pub(super) fn take_ran(&mut self) -> Option<String> {
+        self.ran.take()
+    }
+
+    /// Runs the typed command, or else the selected entry.
+    fn run(&mut self) -> Outcome {
+        let input = self.input.trim().to_string();
+        if let Some(command) = self.command() {
+            return match self.parse(command, &input) {
+                Ok(outcome) => {
+                    self.ran = Some(input);
+                    outcome
+                }
+                Err(err) => {
+                    self.error = Some(format!("{err:#}"));
+                    Outcome::Keep
+                }
+            };
+        }
+
+        let selected = self.list.selected.and_then(|i| self.matches.get(i));
+        match selected.map(|index| self.entries[*index]) {
+            Some(Entry::Command(command)) => {
+                self.input = format!("{} ", command.name);
+                self.filter();
+                Outcome::Keep
+            }
+            Some(Entry::Binding(binding)) => {
+                self.ran = Some(binding.description.to_string());
+                key_event(binding).map_or(Outcome::Close, Outcome::Run)
+            }
+            None => {
+                self.error = Some(format!("No command matches {input}"));
+                Outcome::Keep
+            }
+        }
+    }
+
+    /// The command whose name and arguments are typed, e.g. `goto 120`.
+    fn command(&self) -> Option<&'static Command> {
+        let (name, _) = self.input.trim_start().split_once(' ')?;
+        self.entries.iter().find_map(|entry| match entry {
+            Entry::Command(command) if command.name == name => Some(*command),
+            _ => None,
+        })
+    }
+
+    fn parse(&self, command: &Command, input: &str) -> Result<Outcome> {
+        let args: Vec<&str> = input.split_whitespace().skip(1).collect();
+        match (command.name, args.as_slice()) {
+            ("open", [page]) => Ok(Outcome::Open(page.parse()?)),
+            ("open", [name, section]) => Ok(Outcome::Open(PageRef::new(name, Some(section)))),
+            ("goto", [_, ..]) => {
+                let headings = self.headings.as_deref().unwrap_or_default();
+                goto(&args.join(" "), headings).map(Outcome::ScrollTo)
+            }
+            _ => bail!("Usage: {}", command.usage),
+        }
+    }
+
+    /// Replaces the argument with the next or previous completion.
+    fn complete(&mut self, forward: bool) {
+        let (base, index) = match self.completion.take() {
+            Some((base, index)) => (base, Some(index)),
+            None => (self.input.clone(), None),
+        };
+        let Some((name, argument)) = base.trim_start().split_once(' ') else {
+            return;
+        };
+
+        let argument = argument.trim_start().to_lowercase();
+        let candidates: Vec<&str> = match name {
+            "open" => self.pages.iter().map(String::as_str).collect(),
+            "goto" => self
+                .headings
+                .iter()
+                .flatten()
+                .map(|(_, heading)| heading.as_str())
+                .collect(),
+            _ => Vec::new(),
+        };
+        let candidates: Vec<&str> = candidates
+            .into_iter()
+            .filter(|candidate| candidate.to_lowercase().starts_with(&argument))
+            .collect();
+        if candidates.is_empty() {
+            self.error = Some(format!("No completions for {}", base.trim()));
+            return;
+        }
+
+        let last = candidates.len() - 1;
+        let index = match (index, forward) {
+            (None, true) => 0,
+            (None, false) => last,
+            (Some(index), true) if index < last => index + 1,
+            (Some(_), true) => 0,
+            (Some(index), false) => index.checked_sub(1).unwrap_or(last),
+        };
+        self.input = format!("{name} {}", candidates[index]);
+        self.completion = Some((base, index));
+        self.error = None;
+    }
+
+    /// Shows the previous or next command of the history.
+    fn recall(&mut self, previous: bool) {
+        if self.history.is_empty() {
+            return;
+        }
+
+        let last = self.history.len() - 1;
+        self.recalled = match (self.recalled, previous) {
+            (None, true) => Some(last),
+            (None, false) => return,
+            (Some(index), true) => Some(index.saturating_sub(1)),
+            (Some(index), false) if index < last => Some(index + 1),
+            (Some(_), false) => None,
+        };
+        self.input = self
+            .recalled
+            .map(|index| self.history[index].clone())
+            .unwrap_or_default();
+        self.filter();
+    }
+
+    fn filter(&mut self) {
+        let input = self.input.trim();
+        let mut matches: Vec<(usize, usize)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| Some((entry.matches(input)?, index)))
+            .collect();
+        matches.sort();
+
+        self.matches = matches.into_iter().map(|(_, index)| index).collect();
+        self.list = ListState::default();
+        if !self.matches.is_empty() {
+            self.list.select(Some(0));
+        }
+        self.error = None;
+    }
+
+    pub(super) fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let theme = get_theme();
+
+        let inner = render_frame(area, buf, "Commands", 72, 24);
+        let [input, list, _, message] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Min(0),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .areas(inner);
+
+        Line::from(vec![
+            Span::styled(format!(" :{}", self.input), theme.base),
+            Span::styled(" ", theme.base.reversed()),
+        ])
+        .render(input, buf);
+
+        if self.command().is_some() {
+            Line::from(" Tab to complete, Enter to run")
+                .style(theme.list.inactive)
+                .render(list, buf);
+        } else if self.matches.is_empty() {
+            Line::from(" No matching commands")
+                .style(theme.list.inactive)
+                .render(list, buf);
+        } else {
+            let width = inner.width as usize;
+            let builder = ListBuilder::new(|context| {
+                let entry = self.entries[self.matches[context.index]];
+                let keys = match entry {
+                    Entry::Command(command) => format!(":{}", command.usage),
+                    Entry::Binding(binding) => format_keys(binding),
+                };
+                let text_width = width.saturating_sub(keys.chars().count() + 3);
+                let text = format!(" {:<text_width$} ", entry.text());
+
+                let style = if context.is_selected {
+                    theme.list.selected
+                } else {
+                    theme.list.active
+                };
+                let line = Line::from(vec![
+                    Span::styled(text, style),
+                    Span::styled(format!("{keys} "), style.bold()),
+                ]);
+                (line, 1)
+            });
+
+            ListView::new(builder, self.matches.len())
+                .infinite_scrolling(false)
+                .render(list, buf, &mut self.list);
+        }
+
+        let (text, style) = match &self.error {
+            Some(error) => (format!(" {error}"), theme.base),
+            None => (
+                String::from(" Tab to select, ↑/↓ for past commands, Enter to run"),
+                theme.list.inactive,
+            ),
+        };
+        Line::from(text).style(style).render(message, buf);
+    }
+}
+
+/// The key event that runs the action of a binding.
+fn key_event(binding: &Binding) -> Option<KeyEvent> {
+    binding.keys.iter().find_map(|key| key.event())
+}
+
+/// Returns the line of a line number, counted from 1, or of a heading.
+/// Headings match exactly, else by their start, else anywhere, ignoring case.
+fn goto(target: &str, headings: &[(usize, String)]) -> Result<usize> {
+    if let Ok(line) = target.parse::<usize>() {
+        return Ok(line.saturating_sub(1));
+    }
+
+    let target = target.to_lowercase();
+    let find = |matches: &dyn Fn(&str) -> bool| {
+        headings
+            .iter()
+            .find(|(_, heading)| matches(&heading.to_lowercase()))
+            .map(|(line, _)| *line)
+    };
+    find(&|heading| heading == target)
+        .or_else(|| find(&|heading| heading.starts_with(&target)))
+        .or_else(|| find(&|heading| heading.contains(&target)))
+        .ok_or_else(|| anyhow!("No section named {target}"))
+}
+
+/// Matches the characters of the input in order, ignoring case. Returns a
+/// score, lower is better: the characters skipped before and between them.
+fn fuzzy_match(input: &str, text: &str) -> Option<usize> {
+    let mut score = 0;
+    let mut chars = text.chars().flat_map(char::to_lowercase);
+    for ch in input.chars().flat_map(char::to_lowercase) {
+        if ch == ' ' {
+            continue;
+        }
+        score += chars.by_ref().position(|text_ch| text_ch == ch)?;
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_match() {
+        assert_eq!(fuzzy_match("", "Export"), Some(0));
+        assert_eq!(fuzzy_match("exp", "Export to Markdown"), Some(0));
+        assert_eq!(fuzzy_match("thm", "Change theme"), Some(8));
+        assert_eq!(fuzzy_match("ch th", "Change theme"), Some(5));
+        assert_eq!(fuzzy_match("xyz", "Change theme"), None);
+    }
+
+    #[test]
+    fn test_goto() {
+        let headings = vec![
+            (0, String::from("NAME")),
+            (4, String::from("DESCRIPTION")),
+            (20, String::from("SEE ALSO")),
+        ];
+        assert_eq!(goto("120", &headings).unwrap(), 119);
+        assert_eq!(goto("description", &headings).unwrap(), 4);
+        assert_eq!(goto("see", &headings).unwrap(), 20);
+        assert_eq!(goto("also", &headings).unwrap(), 20);
+        assert!(goto("options", &headings).is_err());
+    }
+
+    #[test]
+    fn test_parse() {
+        let palette =
+            CommandPalette::new(Context::Reader, Vec::new(), Vec::new(), Some(Vec::new()));
+        let open = |input: &str| match palette.parse(&COMMANDS[0], input) {
+            Ok(Outcome::Open(page)) => Some(page),
+            _ => None,
+        };
+        assert_eq!(
+            open("open ssh_config 5"),
+            Some(PageRef::new("ssh_config", Some("5")))
+        );
+        assert_eq!(
+            open("open ssh_config(5)"),
+            Some(PageRef::new("ssh_config", Some("5")))
+        );
+        assert_eq!(open("open"), None);
+        assert!(matches!(
+            palette.parse(&COMMANDS[1], "goto 120"),
+            Ok(Outcome::ScrollTo(119))
+        ));
+    }
+}
@@ -0,0 +1,98 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::prelude::*;
+
+use super::{render_frame, Outcome};
+use crate::core::{PageRef, Source};
+use crate::ui::theme::get_theme;
+
+/// What to do with the entered page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PromptTarget {
+    /// Open the page side by side with the displayed one.
+    Split,
+    /// Compare the page or file with the displayed one.
+    Diff,
+}
+
+/// Asks for a page to open next to or compare with the displayed one.
+pub(crate) struct PagePrompt {
+    target: PromptTarget,
+    /// The displayed page, opened again in a split if no page is entered.
+    current: Option<PageRef>,
+    input: String,
+    error: Option<String>,
+}
+
+impl PagePrompt {
+    pub(crate) fn new(target: PromptTarget, current: Option<PageRef>) -> Self {
+        Self {
+            target,
+            current,
+            input: String::new(),
+            error: None,
+        }
+    }
+
+    pub(super) fn on_key(&mut self, key: &KeyEvent) -> Outcome {
+        match key.code {
+            KeyCode::Enter => match self.outcome() {
+                Some(outcome) => return outcome,
+                None => {
+                    self.error = Some(match self.target {
+                        PromptTarget::Split => String::from("Enter a page, e.g. ssh_config(5)"),
+                        PromptTarget::Diff => String::from("Enter a page or a file"),
+                    });
+                }
+            },
+            KeyCode::Esc => return Outcome::Close,
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Char(ch) => self.input.push(ch),
+            _ => {}
+        }
+        Outcome::Keep
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        let input = self.input.trim();
+        match self.target {
+            PromptTarget::Split if input.is_empty() => self.current.clone().map(Outcome::OpenSplit),
+            PromptTarget::Split => input.parse().ok().map(Outcome::OpenSplit),
+            PromptTarget::Diff => input.parse::<Source>().ok().map(Outcome::Diff),
+        }
+    }
+
+    pub(super) fn render(&self, area: Rect, buf: &mut Buffer) {
+        let theme = get_theme();
+
+        let title = match (self.target, &self.current) {
+            (PromptTarget::Split, _) => String::from("Open side by side"),
+            (PromptTarget::Diff, Some(current)) => format!("Compare {current} with"),
+            (PromptTarget::Diff, None) => String::from("Compare with"),
+        };
+        let inner = render_frame(area, buf, &title, 48, 5);
+        let [input, hint] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(2), Constraint::Length(1)])
+            .areas(inner);
+
+        Line::from(vec![
+            Span::styled(format!(" Page: {}", self.input), theme.base),
+            Span::styled(" ", theme.base.reversed()),
+        ])
+        .render(input, buf);
+
+        let hint_text = match (&self.error, self.target, &self.current) {
+            (Some(error), _, _) => format!(" {error}"),
+            (None, PromptTarget::Split, Some(current)) => {
+                format!(" Enter to open, empty for {current}")
+            }
+            (None, PromptTarget::Diff, _) => String::from(" A page like printf(1p) or a path"),
+            (None, _, None) => String::from(" Enter to open, Esc to cancel"),
+        };
+        Line::from(hint_text)
+            .style(theme.list.inactive)
+            .render(hint, buf);
+    }
+}
@@ -0,0 +1,89 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::prelude::*;
+use tui_widget_list::{ListBuilder, ListState, ListView};
+
+use super::{render_frame, Outcome};
+use crate::core::History;
+use crate::ui::theme::get_theme;
+
+/// Lists the recently read pages, most recent first.
+pub(crate) struct RecentPopup {
+    list: ListState,
+}
+
+impl RecentPopup {
+    pub(crate) fn new(history: &History) -> Self {
+        let mut list = ListState::default();
+        if !history.entries().is_empty() {
+            list.select(Some(0));
+        }
+
+        Self { list }
+    }
+
+    pub(super) fn on_key(&mut self, key: &KeyEvent, history: &History) -> Outcome {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.list.next(),
+            KeyCode::Char('k') | KeyCode::Up => self.list.previous(),
+            KeyCode::Enter => {
+                if let Some(entry) = self.list.selected.and_then(|i| history.entries().get(i)) {
+                    return Outcome::Open(entry.page.clone());
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q' | 'r') => return Outcome::Close,
+            _ => {}
+        }
+        Outcome::Keep
+    }
+
+    pub(super) fn render(&mut self, area: Rect, buf: &mut Buffer, history: &History) {
+        let theme = get_theme();
+
+        let entries = history.entries();
+        let height = entries.len().clamp(1, 20) as u16 + 4;
+        let inner = render_frame(area, buf, "Recent", 64, height);
+
+        let [list, _, message] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .areas(inner);
+
+        if entries.is_empty() {
+            Line::from(" No pages read yet")
+                .style(theme.list.inactive)
+                .render(list, buf);
+        }
+
+        let builder = ListBuilder::new(|context| {
+            let entry = &entries[context.index];
+            let heading = entry
+                .position
+                .as_ref()
+                .map(|position| position.heading.as_str())
+                .unwrap_or_default();
+            let text = format!(
+                " {:<24} {}  {heading}",
+                entry.page.to_string(),
+                entry.opened.format("%Y-%m-%d %H:%M"),
+            );
+
+            let mut line = Line::from(text).style(theme.list.active);
+            if context.is_selected {
+                line = line.style(theme.list.selected);
+            }
+            (line, 1)
+        });
+
+        ListView::new(builder, entries.len())
+            .infinite_scrolling(false)
+            .render(list, buf, &mut self.list);
+
+        Line::from(" Enter to open, Esc to close")
+            .style(theme.list.inactive)
+            .render(message, buf);
+    }
+}
@@ -0,0 +1,50 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::prelude::*;
+use ratatui::widgets::Paragraph;
+
+use super::{render_frame, Outcome};
+use crate::core::PageRef;
+use crate::ui::theme::get_theme;
+
+/// Asks whether to continue reading a page where it stopped last time.
+pub(crate) struct ResumePrompt {
+    page: PageRef,
+    heading: String,
+    line: usize,
+}
+
+impl ResumePrompt {
+    pub(crate) fn new(page: PageRef, heading: String, line: usize) -> Self {
+        Self {
+            page,
+            heading,
+            line,
+        }
+    }
+
+    pub(super) fn on_key(&self, key: &KeyEvent) -> Outcome {
+        match key.code {
+            KeyCode::Enter | KeyCode::Char('y') => Outcome::ScrollTo(self.line),
+            KeyCode::Esc | KeyCode::Char('n' | 'q') => Outcome::Close,
+            _ => Outcome::Keep,
+        }
+    }
+
+    pub(super) fn render(&self, area: Rect, buf: &mut Buffer) {
+        let theme = get_theme();
+
+        let inner = render_frame(area, buf, "Resume reading", 52, 6);
+
+        let place = if self.heading.is_empty() {
+            String::from("where you left off")
+        } else {
+            format!("at {}", self.heading)
+        };
+        let lines = vec![
+            Line::from(format!(" Continue {} {place}?", self.page)).style(theme.base),
+            Line::default(),
+            Line::from(" Enter to resume, Esc to start at the top").style(theme.list.inactive),
+        ];
+        Paragraph::new(lines).render(inner, buf);
+    }
+}
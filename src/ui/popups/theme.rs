@@ -0,0 +1,110 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::prelude::*;
+use tui_widget_list::{ListBuilder, ListState, ListView};
+
+use super::{render_frame, Outcome};
+use crate::ui::theme::{available_themes, get_theme, set_theme, ThemeSelection};
+
+/// Lists the available themes and previews the selected one live.
+pub(crate) struct ThemePicker {
+    themes: Vec<String>,
+    list: ListState,
+    original: ThemeSelection,
+    error: Option<String>,
+}
+
+impl ThemePicker {
+    pub(crate) fn new(current: &ThemeSelection) -> Self {
+        let themes = available_themes();
+
+        let mut list = ListState::default();
+        list.select(themes.iter().position(|name| *name == current.name));
+
+        Self {
+            themes,
+            list,
+            original: current.clone(),
+            error: None,
+        }
+    }
+
+    fn selected(&self) -> Option<ThemeSelection> {
+        let name = self.themes.get(self.list.selected?)?;
+        Some(ThemeSelection {
+            name: name.clone(),
+            ..self.original.clone()
+        })
+    }
+
+    fn preview(&mut self) {
+        let Some(selection) = self.selected() else {
+            return;
+        };
+
+        match selection.build() {
+            Ok(theme) => {
+                set_theme(theme);
+                self.error = None;
+            }
+            Err(err) => self.error = Some(format!("{err:#}")),
+        }
+    }
+
+    pub(super) fn on_key(&mut self, key: &KeyEvent, current: &mut ThemeSelection) -> Outcome {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.list.next();
+                self.preview();
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.list.previous();
+                self.preview();
+            }
+            KeyCode::Enter if self.error.is_none() => {
+                if let Some(selection) = self.selected() {
+                    *current = selection;
+                }
+                return Outcome::Close;
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                if let Ok(theme) = self.original.build() {
+                    set_theme(theme);
+                }
+                return Outcome::Close;
+            }
+            _ => {}
+        }
+        Outcome::Keep
+    }
+
+    pub(super) fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let theme = get_theme();
+
+        let height = self.themes.len() as u16 + 3;
+        let inner = render_frame(area, buf, "Theme", 40, height);
+
+        let [list, message] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .areas(inner);
+
+        let builder = ListBuilder::new(|context| {
+            let mut line = Line::from(format!(" {}", self.themes[context.index]));
+            line = line.style(theme.list.active);
+            if context.is_selected {
+                line = line.style(theme.list.selected);
+            }
+            (line, 1)
+        });
+
+        ListView::new(builder, self.themes.len())
+            .infinite_scrolling(false)
+            .render(list, buf, &mut self.list);
+
+        let hint = match &self.error {
+            Some(err) => Line::from(format!(" {err}")).style(theme.highlight.active),
+            None => Line::from(" Enter to apply, Esc to cancel").style(theme.list.inactive),
+        };
+        hint.render(message, buf);
+    }
+}
@@ -0,0 +1,151 @@
+use anyhow::{bail, Result};
+use ratatui::{
+    crossterm::terminal,
+    style::{Color, Modifier, Style},
+};
+use std::io::{self, IsTerminal, Write};
+
+use super::theme::get_theme;
+use crate::core::{Manual, Source};
+
+/// The width used when stdout is not a terminal.
+const DEFAULT_WIDTH: u16 = 80;
+
+/// Prints man pages or man page files to stdout instead of opening the reader, styled with the
+/// active theme when stdout is a terminal and as plain text otherwise.
+///
+/// Only the sections with the given headings are printed, unless there are none.
+pub(crate) fn print(sources: &[Source], width: Option<u16>, sections: &[String]) -> Result<()> {
+    let styled = io::stdout().is_terminal();
+    let width = width
+        .or_else(|| {
+            styled
+                .then(terminal::size)
+                .and_then(Result::ok)
+                .map(|(cols, _)| cols)
+        })
+        .unwrap_or(DEFAULT_WIDTH);
+
+    let mut out = io::stdout().lock();
+    for (index, source) in sources.iter().enumerate() {
+        let mut manual = source.read(&width.to_string())?;
+        if !sections.is_empty() {
+            manual = manual.sections(sections);
+            if manual.is_empty() {
+                bail!("{source} has no section named {}", sections.join(", "));
+            }
+        }
+
+        if index > 0 {
+            writeln!(out)?;
+        }
+        write_manual(&mut out, &manual, styled)?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn write_manual(out: &mut impl Write, manual: &Manual, styled: bool) -> io::Result<()> {
+    let theme = get_theme();
+    for line in &manual.lines {
+        let mut text = String::new();
+        for fragment in line {
+            // Plain text keeps the colors of the terminal, only emphasis is styled.
+            let sgr = if styled {
+                sgr(theme.man.get(fragment.emphasis))
+            } else {
+                String::new()
+            };
+            if sgr.is_empty() {
+                text.push_str(&fragment.text);
+            } else {
+                text.push_str(&format!("{sgr}{}\x1b[0m", fragment.text));
+            }
+        }
+        writeln!(out, "{}", text.trim_end())?;
+    }
+    Ok(())
+}
+
+/// Returns the SGR escape sequence for the foreground color and modifiers of a style.
+fn sgr(style: Style) -> String {
+    const MODIFIERS: [(Modifier, &str); 6] = [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::CROSSED_OUT, "9"),
+    ];
+
+    let mut codes: Vec<String> = MODIFIERS
+        .iter()
+        .filter(|(modifier, _)| style.add_modifier.contains(*modifier))
+        .map(|(_, code)| code.to_string())
+        .collect();
+    codes.extend(style.fg.and_then(foreground));
+
+    if codes.is_empty() {
+        String::new()
+    } else {
+        format!("\x1b[{}m", codes.join(";"))
+    }
+}
+
+fn foreground(color: Color) -> Option<String> {
+    let code = match color {
+        Color::Reset => return None,
+        Color::Black => 30,
+        Color::Red => 31,
+        Color::Green => 32,
+        Color::Yellow => 33,
+        Color::Blue => 34,
+        Color::Magenta => 35,
+        Color::Cyan => 36,
+        Color::Gray => 37,
+        Color::DarkGray => 90,
+        Color::LightRed => 91,
+        Color::LightGreen => 92,
+        Color::LightYellow => 93,
+        Color::LightBlue => 94,
+        Color::LightMagenta => 95,
+        Color::LightCyan => 96,
+        Color::White => 97,
+        Color::Indexed(index) => return Some(format!("38;5;{index}")),
+        Color::Rgb(r, g, b) => return Some(format!("38;2;{r};{g};{b}")),
+    };
+    Some(code.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sgr() {
+        assert_eq!(sgr(Style::default()), "");
+        assert_eq!(
+            sgr(Style::default()
+                .fg(Color::Rgb(248, 154, 99))
+                .add_modifier(Modifier::BOLD)),
+            "\x1b[1;38;2;248;154;99m"
+        );
+        assert_eq!(
+            sgr(Style::default()
+                .fg(Color::Indexed(209))
+                .add_modifier(Modifier::UNDERLINED)),
+            "\x1b[4;38;5;209m"
+        );
+        assert_eq!(sgr(Style::default().fg(Color::LightBlue)), "\x1b[94m");
+    }
+
+    #[test]
+    fn test_write_plain() {
+        let mut manual = Manual::plain("NAME  ");
+        manual.push(" tar", crate::core::Emphasis::Bold);
+
+        let mut out = Vec::new();
+        write_manual(&mut out, &manual, false).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "NAME   tar\n");
+    }
+}
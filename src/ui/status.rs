@@ -0,0 +1,205 @@
+use serde::Deserialize;
+use std::{env, path::Path, sync::RwLock};
+
+use crate::core::Footer;
+
+/// A field of the status line of the reader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum StatusField {
+    /// The page name and section, e.g. `tar(1)`.
+    Page,
+    /// The source file of the page, e.g. `/usr/share/man/man1/tar.1.gz`.
+    Source,
+    /// The date in the footer of the page.
+    Date,
+    /// The version in the footer of the page, e.g. `GNU tar 1.35`.
+    Version,
+    /// The heading of the section at the top of the screen.
+    Heading,
+    /// The line at the top of the screen and the number of lines.
+    Line,
+    /// How much of the page was read.
+    Percent,
+}
+
+impl StatusField {
+    pub(crate) const ALL: [StatusField; 7] = [
+        StatusField::Page,
+        StatusField::Source,
+        StatusField::Date,
+        StatusField::Version,
+        StatusField::Heading,
+        StatusField::Line,
+        StatusField::Percent,
+    ];
+}
+
+/// The fields from the least to the most important. On narrow terminals the
+/// least important fields are shortened first, and then left out.
+const PRIORITY: [StatusField; 7] = [
+    StatusField::Source,
+    StatusField::Date,
+    StatusField::Version,
+    StatusField::Heading,
+    StatusField::Page,
+    StatusField::Line,
+    StatusField::Percent,
+];
+
+/// The length of the heading in the compact status line.
+const COMPACT_HEADING: usize = 16;
+
+const SEPARATOR: &str = " │ ";
+
+static FIELDS: RwLock<Option<Vec<StatusField>>> = RwLock::new(None);
+
+/// Sets the fields of the status line, in the order they are shown.
+pub(crate) fn set_status_fields(fields: Vec<StatusField>) {
+    *FIELDS.write().unwrap() = Some(fields);
+}
+
+/// Returns the fields of the status line, all of them unless configured otherwise.
+pub(crate) fn status_fields() -> Vec<StatusField> {
+    FIELDS
+        .read()
+        .unwrap()
+        .clone()
+        .unwrap_or_else(|| StatusField::ALL.to_vec())
+}
+
+/// The page and reading position shown in the status line of the reader.
+#[derive(Debug, Default)]
+pub(crate) struct Status {
+    pub(crate) page: Option<String>,
+    pub(crate) source: Option<String>,
+    pub(crate) footer: Option<Footer>,
+    pub(crate) heading: Option<String>,
+    /// The first visible line, counted from 0, and the number of lines.
+    pub(crate) line: usize,
+    pub(crate) lines: usize,
+    pub(crate) percent: usize,
+}
+
+impl Status {
+    /// Returns the text of a field in full and in compact form, if it has a value.
+    fn field(&self, field: StatusField) -> Option<(String, String)> {
+        let same = |text: &str| Some((text.to_string(), text.to_string()));
+        match field {
+            StatusField::Page => same(self.page.as_deref()?),
+            StatusField::Source => {
+                let source = self.source.as_deref()?;
+                let name = Path::new(source).file_name()?.to_string_lossy();
+                Some((source.to_string(), name.to_string()))
+            }
+            StatusField::Date => same(&self.footer.as_ref()?.date),
+            StatusField::Version => same(&self.footer.as_ref()?.version),
+            StatusField::Heading => {
+                let heading = self.heading.as_deref()?;
+                let mut compact: String = heading.chars().take(COMPACT_HEADING).collect();
+                if compact.len() < heading.len() {
+                    compact.push('…');
+                }
+                Some((heading.to_string(), compact))
+            }
+            StatusField::Line => Some((
+                format!("line {}/{}", self.line + 1, self.lines),
+                format!("{}", self.line + 1),
+            )),
+            StatusField::Percent => same(&format!("{}%", self.percent)),
+        }
+    }
+
+    /// Joins the fields in the given order. If the line is wider than `width`,
+    /// the least important field is shortened, or else left out, and so on.
+    pub(crate) fn line(&self, fields: &[StatusField], width: usize) -> String {
+        let mut entries: Vec<(StatusField, String, String)> = fields
+            .iter()
+            .filter_map(|field| {
+                let (full, compact) = self.field(*field)?;
+                Some((*field, full, compact))
+            })
+            .collect();
+
+        let text = |entries: &[(StatusField, String, String)]| {
+            let texts: Vec<&str> = entries.iter().map(|(_, text, _)| text.as_str()).collect();
+            format!(" {} ", texts.join(SEPARATOR))
+        };
+        let fits = |entries: &[(StatusField, String, String)]| {
+            entries.is_empty() || text(entries).chars().count() <= width
+        };
+
+        for field in PRIORITY {
+            if fits(&entries) {
+                break;
+            }
+            for (_, full, compact) in entries.iter_mut().filter(|(f, ..)| *f == field) {
+                *full = compact.clone();
+            }
+            if !fits(&entries) {
+                entries.retain(|(f, ..)| *f != field);
+            }
+        }
+        if entries.is_empty() {
+            return String::new();
+        }
+        text(&entries)
+    }
+}
+
+/// Shortens a path in the home directory, e.g. `~/docs/foo.1`.
+pub(crate) fn display_path(path: &Path) -> String {
+    let home = env::var_os("HOME").filter(|home| !home.is_empty());
+    match home.and_then(|home| path.strip_prefix(home).ok().map(Path::to_path_buf)) {
+        Some(relative) => format!("~/{}", relative.display()),
+        None => path.display().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn status() -> Status {
+        Status {
+            page: Some(String::from("tar(1)")),
+            source: Some(String::from("/usr/share/man/man1/tar.1.gz")),
+            footer: Some(Footer {
+                version: String::from("GNU tar 1.35"),
+                date: String::from("2023-07-18"),
+            }),
+            heading: Some(String::from("DESCRIPTION")),
+            line: 41,
+            lines: 300,
+            percent: 14,
+        }
+    }
+
+    #[test]
+    fn test_line() {
+        let status = status();
+        assert_eq!(
+            status.line(&StatusField::ALL, 200),
+            " tar(1) │ /usr/share/man/man1/tar.1.gz │ 2023-07-18 │ GNU tar 1.35 │ DESCRIPTION │ line 42/300 │ 14% "
+        );
+        assert_eq!(
+            status.line(&[StatusField::Percent, StatusField::Page], 200),
+            " 14% │ tar(1) "
+        );
+    }
+
+    #[test]
+    fn test_line_compact() {
+        let status = status();
+        assert_eq!(
+            status.line(&StatusField::ALL, 84),
+            " tar(1) │ tar.1.gz │ 2023-07-18 │ GNU tar 1.35 │ DESCRIPTION │ line 42/300 │ 14% "
+        );
+        assert_eq!(
+            status.line(&StatusField::ALL, 40),
+            " tar(1) │ line 42/300 │ 14% "
+        );
+        assert_eq!(status.line(&StatusField::ALL, 12), " 42 │ 14% ");
+        assert_eq!(status.line(&StatusField::ALL, 3), "");
+    }
+}
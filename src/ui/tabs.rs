@@ -0,0 +1,224 @@
+use ratatui::prelude::*;
+use std::mem;
+
+use super::app::{load_commands_in_background, ActiveState, ActiveWidget, AppState};
+use super::events::{EventController, IStatefulWidget};
+use super::pages::{DiffPage, HomePage, ListPage, ListPageState, ReaderPage};
+use super::popups::Popup;
+use super::theme::get_theme;
+
+/// An inactive tab.
+pub(crate) struct Tab {
+    state: ActiveState,
+    /// The list the tab goes back to, if it shows another page. Each tab keeps
+    /// its own section, search and selection.
+    list: Option<ListPageState>,
+}
+
+/// Creates the widget of a page. Widgets register their event listeners when
+/// they are created, thus the page of a tab gets a new widget when it is shown.
+fn widget(state: &ActiveState, controller: &EventController) -> ActiveWidget {
+    match state {
+        ActiveState::Home(_) => ActiveWidget::Home(IStatefulWidget::new(HomePage {}, controller)),
+        ActiveState::List(_) => {
+            ActiveWidget::List(IStatefulWidget::new(ListPage::new(controller), controller))
+        }
+        ActiveState::Read(_) => ActiveWidget::Read(IStatefulWidget::new(
+            ReaderPage::new(controller),
+            controller,
+        )),
+        ActiveState::Diff(_) => ActiveWidget::Diff(IStatefulWidget::new(DiffPage, controller)),
+    }
+}
+
+impl AppState {
+    /// Returns the number of open tabs.
+    pub(crate) fn num_tabs(&self) -> usize {
+        self.tabs.len() + 1
+    }
+
+    /// Returns the states of all tabs in order.
+    fn tab_states(&self) -> Vec<&ActiveState> {
+        let mut states: Vec<_> = self.tabs.iter().map(|tab| &tab.state).collect();
+        states.insert(self.active_tab, &self.active_state);
+        states
+    }
+
+    /// Replaces the page of the active tab. A replaced list is kept to go back to it.
+    pub(crate) fn replace_tab(&mut self, state: ActiveState, controller: &EventController) {
+        self.active_page = widget(&state, controller);
+        if let ActiveState::List(list) = mem::replace(&mut self.active_state, state) {
+            self.list = Some(list);
+        }
+    }
+
+    /// Opens a tab after the last tab and activates it.
+    pub(crate) fn add_tab(&mut self, state: ActiveState, controller: &EventController) {
+        let tab = Tab { state, list: None };
+        let previous = self.swap_active(tab, controller);
+        self.tabs.insert(self.active_tab, previous);
+        self.active_tab = self.tabs.len();
+    }
+
+    /// Activates the tab at an index.
+    pub(crate) fn switch_tab(&mut self, index: usize, controller: &EventController) {
+        if index == self.active_tab || index >= self.num_tabs() {
+            return;
+        }
+        self.leave_reader();
+
+        let inactive_index = if index < self.active_tab {
+            index
+        } else {
+            index - 1
+        };
+        let tab = self.tabs.remove(inactive_index);
+        let previous = self.swap_active(tab, controller);
+
+        let previous_index = if self.active_tab < index {
+            self.active_tab
+        } else {
+            self.active_tab - 1
+        };
+        self.tabs.insert(previous_index, previous);
+        self.active_tab = index;
+
+        self.refresh_active_tab();
+    }
+
+    pub(crate) fn next_tab(&mut self, controller: &EventController) {
+        self.switch_tab((self.active_tab + 1) % self.num_tabs(), controller);
+    }
+
+    pub(crate) fn previous_tab(&mut self, controller: &EventController) {
+        let num_tabs = self.num_tabs();
+        self.switch_tab((self.active_tab + num_tabs - 1) % num_tabs, controller);
+    }
+
+    /// Closes the active tab and activates the next one, or the previous one if it was the last.
+    pub(crate) fn close_tab(&mut self, controller: &EventController) {
+        if self.tabs.is_empty() {
+            self.notify("Cannot close the last tab");
+            return;
+        }
+        self.leave_reader();
+
+        let index = self.active_tab.min(self.tabs.len() - 1);
+        let tab = self.tabs.remove(index);
+        self.swap_active(tab, controller);
+        self.active_tab = index;
+
+        self.refresh_active_tab();
+    }
+
+    /// Whether key presses go to a popup or a text input instead of switching tabs.
+    pub(crate) fn is_typing(&self) -> bool {
+        self.popup.is_some()
+            || match &self.active_state {
+                ActiveState::Home(_) | ActiveState::Diff(_) => false,
+                ActiveState::List(state) => state.is_searching(),
+                ActiveState::Read(state) => state.is_searching(),
+            }
+    }
+
+    /// Activates a tab and returns the previously active one.
+    fn swap_active(&mut self, tab: Tab, controller: &EventController) -> Tab {
+        self.active_page = widget(&tab.state, controller);
+        Tab {
+            state: mem::replace(&mut self.active_state, tab.state),
+            list: mem::replace(&mut self.list, tab.list),
+        }
+    }
+
+    /// Updates a tab that was inactive while notes, marks or collections changed.
+    fn refresh_active_tab(&mut self) {
+        match &mut self.active_state {
+            ActiveState::Home(_) | ActiveState::Diff(_) => {}
+            ActiveState::List(state) => {
+                if let Some(section) = state.refresh_collection(&self.collections) {
+                    load_commands_in_background(self, section);
+                }
+            }
+            ActiveState::Read(state) => {
+                state.set_notes(&self.notes);
+                state.set_marks(&self.marks);
+            }
+        }
+    }
+
+    /// Passes loaded commands to the lists of all tabs that show the section,
+    /// including the lists hidden behind a page.
+    pub(crate) fn set_loaded_commands(&mut self, commands: &[String], section: usize) {
+        fn shown(state: &mut ActiveState) -> Option<&mut ListPageState> {
+            match state {
+                ActiveState::List(list) => Some(list),
+                _ => None,
+            }
+        }
+        let lists = self
+            .tabs
+            .iter_mut()
+            .flat_map(|tab| shown(&mut tab.state).into_iter().chain(tab.list.as_mut()))
+            .chain(shown(&mut self.active_state))
+            .chain(self.list.as_mut());
+
+        for list in lists {
+            if list.selected_section_index() == section {
+                list.set_loaded_commands(commands);
+            }
+        }
+    }
+
+    /// Remembers where reading stopped in all tabs, e.g. before quitting.
+    pub(crate) fn leave_all_tabs(&mut self) {
+        for tab in &self.tabs {
+            if let ActiveState::Read(state) = &tab.state {
+                if let Some(page) = state.saved_page() {
+                    self.history.set_position(&page, state.position());
+                }
+            }
+        }
+        self.leave_reader();
+    }
+
+    /// Whether the user is asked to resume reading, see [`AppState::leave_reader`].
+    pub(crate) fn is_resume_pending(&self) -> bool {
+        matches!(self.popup, Some(Popup::Resume(_)))
+    }
+}
+
+/// The titles of the open tabs across the top.
+pub(crate) struct TabBar;
+
+impl StatefulWidget for TabBar {
+    type State = AppState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let theme = get_theme();
+        buf.set_style(area, theme.base);
+
+        let spans: Vec<Span> = state
+            .tab_states()
+            .into_iter()
+            .enumerate()
+            .map(|(index, tab)| {
+                let title = match tab {
+                    ActiveState::Home(_) => String::from("Home"),
+                    ActiveState::List(_) => String::from("Browse"),
+                    ActiveState::Read(state) => {
+                        state.title().unwrap_or_else(|| String::from("Error"))
+                    }
+                    ActiveState::Diff(state) => state.title(),
+                };
+                let style = if index == state.active_tab {
+                    theme.list.selected
+                } else {
+                    theme.list.inactive
+                };
+                Span::styled(format!(" {} {title} ", index + 1), style)
+            })
+            .collect();
+
+        Line::from(spans).render(area, buf);
+    }
+}
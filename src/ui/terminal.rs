@@ -6,8 +6,10 @@ use ratatui::crossterm::terminal::{
 };
 use ratatui::layout::Rect;
 use ratatui::prelude::CrosstermBackend;
-use std::io::{stderr, stdout, Stderr};
+use std::io::{self, stderr, stdout, Read, Stderr};
 use std::ops::{Deref, DerefMut};
+use std::sync::mpsc;
+use std::thread;
 
 pub(crate) struct Terminal {
     terminal: ratatui::Terminal<CrosstermBackend<Stderr>>,
@@ -35,11 +37,74 @@ impl Terminal {
         Ok(())
     }
 
+    /// Enters the alternate screen again after `stop` and redraws everything.
+    pub fn restore(&mut self) -> Result<()> {
+        execute!(stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+        enable_raw_mode()?;
+        self.terminal.clear()?;
+        Ok(())
+    }
+
     pub fn area(&mut self) -> Rect {
         self.terminal.get_frame().area()
     }
 }
 
+/// Reads a page piped to stdin in the background and sends it in chunks as
+/// it arrives, which may never end, e.g. from `tail -f`. The channel closes
+/// at the end of the input. Stdin is replaced with the terminal meanwhile.
+pub(crate) fn read_stdin() -> Result<mpsc::Receiver<Vec<u8>>> {
+    let mut input = piped_stdin()?;
+    reopen_tty()?;
+
+    let (sx, chunks) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buffer = [0; 64 * 1024];
+        while let Ok(read @ 1..) = input.read(&mut buffer) {
+            if sx.send(buffer[..read].to_vec()).is_err() {
+                break;
+            }
+        }
+    });
+    Ok(chunks)
+}
+
+/// Opens the pipe on stdin again, such that it can still be read once
+/// stdin refers to the terminal.
+#[cfg(unix)]
+fn piped_stdin() -> Result<Box<dyn Read + Send>> {
+    use std::{fs::File, os::fd::AsFd};
+
+    let pipe = io::stdin().as_fd().try_clone_to_owned()?;
+    Ok(Box::new(File::from(pipe)))
+}
+
+#[cfg(not(unix))]
+fn piped_stdin() -> Result<Box<dyn Read + Send>> {
+    Ok(Box::new(io::stdin()))
+}
+
+/// Replaces stdin with the controlling terminal once a page was read from a
+/// pipe, so that key events and programs started from mantui read from it.
+#[cfg(unix)]
+fn reopen_tty() -> Result<()> {
+    use std::{fs::File, os::fd::AsRawFd};
+
+    let tty = File::options().read(true).write(true).open("/dev/tty")?;
+    // SAFETY: both descriptors are open for the duration of the call, and
+    // `dup2` only makes stdin refer to the terminal. `tty` closes its own
+    // descriptor when dropped, which leaves the duplicate open.
+    if unsafe { libc::dup2(tty.as_raw_fd(), libc::STDIN_FILENO) } < 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn reopen_tty() -> Result<()> {
+    Ok(())
+}
+
 impl Deref for Terminal {
     type Target = ratatui::Terminal<CrosstermBackend<Stderr>>;
     fn deref(&self) -> &Self::Target {
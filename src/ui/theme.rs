@@ -1,43 +1,226 @@
+use anyhow::{anyhow, Context, Result};
 use ratatui::style::{Color, Style};
 use serde::Deserialize;
-use std::sync::OnceLock;
+use std::{
+    fs,
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
 use tui_theme_builder::ThemeBuilder;
 
-use crate::args::Args;
+use super::colors::ColorSupport;
+use crate::config::config_dir;
+use crate::core::Emphasis;
+
+pub const DEFAULT_THEME: &str = "dark";
+
+const DARK: &str = r##"
+"foreground" = "white"
+"background" = "#101116"
+"muted" = "#454554"
+"accent" = "#f89a63"
+"secondary" = "#9878d1"
+"##;
+
+const LIGHT: &str = r##"
+"foreground" = "#1c1c20"
+"background" = "#fafafa"
+"muted" = "#a0a0a8"
+"accent" = "#c4551c"
+"secondary" = "#6f42c1"
+"##;
+
+const HIGH_CONTRAST: &str = r##"
+"foreground" = "#ffffff"
+"background" = "#000000"
+"muted" = "#bfbfbf"
+"accent" = "#ffff00"
+"secondary" = "#00ffff"
+"##;
+
+const SOLARIZED: &str = r##"
+"foreground" = "#93a1a1"
+"background" = "#002b36"
+"muted" = "#586e75"
+"accent" = "#b58900"
+"secondary" = "#6c71c4"
+"##;
+
+/// The built-in themes. Theme files in the config directory take precedence.
+const BUILTIN_THEMES: [(&str, &str); 4] = [
+    ("dark", DARK),
+    ("light", LIGHT),
+    ("high-contrast", HIGH_CONTRAST),
+    ("solarized", SOLARIZED),
+];
 
 #[derive(Debug, Deserialize)]
+#[serde(from = "ColorsFile")]
 pub struct Colors {
-    pub white: Color,
-    pub black: Color,
-    pub gray: Color,
-    pub orange: Color,
+    pub foreground: Color,
+    pub background: Color,
+    pub muted: Color,
+    pub accent: Color,
+    pub secondary: Color,
+}
+
+/// The colors as written in a theme file, where `secondary` is optional
+/// such that themes written before it existed still load.
+#[derive(Debug, Deserialize)]
+struct ColorsFile {
+    foreground: Color,
+    background: Color,
+    muted: Color,
+    accent: Color,
+    #[serde(default)]
+    secondary: Option<Color>,
+}
+
+impl From<ColorsFile> for Colors {
+    fn from(file: ColorsFile) -> Self {
+        Self {
+            foreground: file.foreground,
+            background: file.background,
+            muted: file.muted,
+            accent: file.accent,
+            secondary: file.secondary.unwrap_or(file.accent),
+        }
+    }
 }
 
 impl Default for Colors {
     fn default() -> Self {
-        let s = r##"
-        "white" = "white"
-        "black" = "#101116"
-        "gray" = "#454554"
-        "lightorange" = "#e0af67"
-        "orange" = "#f89a63"
-        "charcoal" = "#1c1c20"
-        "##;
-        toml::from_str(s).unwrap()
+        toml::from_str(DARK).unwrap()
     }
 }
 
+/// A theme file: the palette plus optional overrides for the styles of man page text.
+#[derive(Debug, Deserialize)]
+struct ThemeFile {
+    #[serde(flatten)]
+    colors: Colors,
+
+    #[serde(default)]
+    man: ManStyleOverrides,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+struct ManStyleOverrides {
+    bold: Option<Style>,
+    italic: Option<Style>,
+    underline: Option<Style>,
+    bold_underline: Option<Style>,
+}
+
+impl ThemeFile {
+    /// Loads a theme, either from `themes/<name>.toml` in the
+    /// config directory or from the built-in themes.
+    fn load(name: &str) -> Result<Self> {
+        if let Some(path) = theme_file(name).filter(|path| path.exists()) {
+            let content = fs::read_to_string(&path)?;
+            return toml::from_str(&content)
+                .with_context(|| format!("failed to parse {}", path.display()));
+        }
+
+        let (_, content) = BUILTIN_THEMES
+            .iter()
+            .find(|(builtin, _)| *builtin == name)
+            .ok_or_else(|| anyhow!("unknown theme: {name}"))?;
+
+        Ok(toml::from_str(content)?)
+    }
+}
+
+fn themes_dir() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("themes"))
+}
+
+fn theme_file(name: &str) -> Option<PathBuf> {
+    themes_dir().map(|dir| dir.join(format!("{name}.toml")))
+}
+
+/// Returns the names of the built-in themes followed by the
+/// themes found in the config directory.
+pub(super) fn available_themes() -> Vec<String> {
+    let mut themes: Vec<String> = BUILTIN_THEMES
+        .iter()
+        .map(|(name, _)| (*name).to_string())
+        .collect();
+
+    let mut custom: Vec<String> = themes_dir()
+        .and_then(|dir| fs::read_dir(dir).ok())
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .filter_map(|path| Some(path.file_stem()?.to_string_lossy().to_string()))
+        .filter(|name| !themes.contains(name))
+        .collect();
+    custom.sort();
+
+    themes.extend(custom);
+    themes
+}
+
+static THEME: RwLock<Option<Arc<Theme>>> = RwLock::new(None);
+
 #[must_use]
-pub(super) fn get_theme() -> &'static Theme {
-    THEME.get().unwrap()
+pub(super) fn get_theme() -> Arc<Theme> {
+    THEME.read().unwrap().clone().unwrap_or_default()
+}
+
+/// Replaces the active theme. The next frame is drawn with the new theme.
+pub fn set_theme(theme: Theme) {
+    *THEME.write().unwrap() = Some(Arc::new(theme));
 }
 
-pub static THEME: OnceLock<Theme> = OnceLock::new();
+/// The selected theme, kept to rebuild the theme at runtime.
+#[derive(Debug, Clone)]
+pub struct ThemeSelection {
+    pub name: String,
+    pub transparent: bool,
+    pub colors: ColorSupport,
+}
+
+impl ThemeSelection {
+    pub fn new(name: Option<String>, transparent: bool, colors: ColorSupport) -> Self {
+        Self {
+            name: name.unwrap_or_else(|| DEFAULT_THEME.to_string()),
+            transparent,
+            colors,
+        }
+    }
+
+    pub fn build(&self) -> Result<Theme> {
+        let file = ThemeFile::load(&self.name)?;
+        let mut theme = Theme::build(&file.colors);
+
+        let man = file.man;
+        theme.man.bold = man.bold.unwrap_or(theme.man.bold);
+        theme.man.italic = man.italic.unwrap_or(theme.man.italic);
+        theme.man.underline = man.underline.unwrap_or(theme.man.underline);
+        theme.man.bold_underline = man.bold_underline.unwrap_or(theme.man.bold_underline);
+
+        if self.transparent {
+            theme.base.bg = None;
+        }
+
+        if self.colors == ColorSupport::Monochrome {
+            theme.make_monochrome();
+        } else {
+            theme.map_styles(|style| self.colors.map_style(style));
+        }
+
+        Ok(theme)
+    }
+}
 
 #[derive(Debug, Clone, ThemeBuilder)]
 #[builder(context=Colors)]
 pub struct Theme {
-    #[style(fg=white,bg=black)]
+    #[style(fg=foreground,bg=background)]
     pub(super) base: Style,
 
     pub(super) list: ListStyle,
@@ -47,6 +230,14 @@ pub struct Theme {
     pub(super) block: BlockStyle,
 
     pub(super) highlight: HighlightStyle,
+
+    pub(super) man: ManStyle,
+
+    pub(super) diff: DiffStyle,
+
+    /// Markers in the gutter of the reader.
+    #[style(fg=accent, bold)]
+    pub(super) marker: Style,
 }
 
 impl Default for Theme {
@@ -57,54 +248,185 @@ impl Default for Theme {
 }
 
 impl Theme {
-    pub fn init(args: &Args) -> Self {
-        let mut theme = Self::default();
-        if args.transparent {
-            theme.base = Style::default().fg(theme.base.fg.unwrap());
+    /// Applies a function to every style of the theme.
+    fn map_styles(&mut self, f: impl Fn(Style) -> Style) {
+        let styles = [
+            &mut self.base,
+            &mut self.list.active,
+            &mut self.list.inactive,
+            &mut self.list.selected,
+            &mut self.search.active,
+            &mut self.search.inactive,
+            &mut self.block.active,
+            &mut self.block.inactive,
+            &mut self.highlight.active,
+            &mut self.highlight.inactive,
+            &mut self.man.bold,
+            &mut self.man.italic,
+            &mut self.man.underline,
+            &mut self.man.bold_underline,
+            &mut self.marker,
+            &mut self.diff.removed,
+            &mut self.diff.added,
+            &mut self.diff.removed_word,
+            &mut self.diff.added_word,
+        ];
+        for style in styles {
+            *style = f(*style);
         }
-        theme
+    }
+
+    /// Removes all colors and relies on bold, underline and reverse instead.
+    fn make_monochrome(&mut self) {
+        self.map_styles(|style| ColorSupport::Monochrome.map_style(style));
+
+        self.list.selected = self.list.selected.reversed();
+        self.list.inactive = Style::default();
+        self.block.inactive = Style::default();
+        self.highlight.active = self.highlight.active.reversed();
+        self.highlight.inactive = self.highlight.inactive.reversed();
+
+        self.man.bold = self.man.bold.bold();
+        self.man.italic = self.man.italic.not_italic().underlined();
+        self.man.underline = self.man.underline.underlined();
+        self.man.bold_underline = self.man.bold_underline.bold().underlined();
+
+        self.diff.removed = self.diff.removed.crossed_out();
+        self.diff.removed_word = self.diff.removed_word.crossed_out().reversed();
+        self.diff.added_word = self.diff.added_word.reversed();
     }
 }
 
 #[derive(Debug, Default, Clone, ThemeBuilder)]
 #[builder(context=Colors)]
 pub(super) struct ListStyle {
-    #[style(fg=white)]
+    #[style(fg=foreground)]
     pub(super) active: Style,
 
-    #[style(fg=gray)]
+    #[style(fg=muted)]
     pub(super) inactive: Style,
 
-    #[style(fg=black, bg=orange)]
+    #[style(fg=background, bg=accent)]
     pub(super) selected: Style,
 }
 
 #[derive(Debug, Default, Clone, ThemeBuilder)]
 #[builder(context=Colors)]
 pub(super) struct SearchStyle {
-    #[style(fg=white)]
+    #[style(fg=foreground)]
     pub(super) active: Style,
 
-    #[style(fg=gray)]
+    #[style(fg=muted)]
     pub(super) inactive: Style,
 }
 
 #[derive(Debug, Default, Clone, ThemeBuilder)]
 #[builder(context=Colors)]
 pub(super) struct BlockStyle {
-    #[style(fg=white)]
+    #[style(fg=foreground)]
     pub(super) active: Style,
 
-    #[style(fg=gray)]
+    #[style(fg=muted)]
     pub(super) inactive: Style,
 }
 
 #[derive(Debug, Default, Clone, ThemeBuilder)]
 #[builder(context=Colors)]
 pub(super) struct HighlightStyle {
-    #[style(fg=black, bg=orange)]
+    #[style(fg=background, bg=accent)]
     pub(super) active: Style,
 
-    #[style(fg=black, bg=orange)]
+    #[style(fg=background, bg=accent)]
     pub(super) inactive: Style,
 }
+
+#[derive(Debug, Default, Clone, ThemeBuilder)]
+#[builder(context=Colors)]
+pub(super) struct ManStyle {
+    #[style(fg=secondary)]
+    pub(super) bold: Style,
+
+    #[style(fg=accent, italic)]
+    pub(super) italic: Style,
+
+    #[style(fg=accent)]
+    pub(super) underline: Style,
+
+    #[style(fg=secondary)]
+    pub(super) bold_underline: Style,
+}
+
+/// The styles of removed and added lines, and of the changed words within them.
+#[derive(Debug, Default, Clone, ThemeBuilder)]
+#[builder(context=Colors)]
+pub(super) struct DiffStyle {
+    #[style(fg=secondary)]
+    pub(super) removed: Style,
+
+    #[style(fg=accent)]
+    pub(super) added: Style,
+
+    #[style(fg=background, bg=secondary)]
+    pub(super) removed_word: Style,
+
+    #[style(fg=background, bg=accent)]
+    pub(super) added_word: Style,
+}
+
+impl ManStyle {
+    pub(super) fn get(&self, emphasis: Emphasis) -> Style {
+        match emphasis {
+            Emphasis::None => Style::default(),
+            Emphasis::Bold => self.bold,
+            Emphasis::Italic => self.italic,
+            Emphasis::Underline => self.underline,
+            Emphasis::BoldUnderline => self.bold_underline,
+            Emphasis::BoldItalic => self.bold.patch(self.italic),
+            Emphasis::ItalicUnderline => self.italic.patch(self.underline),
+            Emphasis::BoldItalicUnderline => self.bold_underline.patch(self.italic),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_builtin_themes() {
+        for (name, _) in BUILTIN_THEMES {
+            assert!(ThemeFile::load(name).is_ok(), "failed to load {name}");
+        }
+    }
+
+    #[test]
+    fn test_man_style_overrides() {
+        let content = r##"
+        foreground = "white"
+        background = "black"
+        muted = "gray"
+        accent = "#f89a63"
+        secondary = "#9878d1"
+
+        [man]
+        bold = { fg = "red", add_modifier = "BOLD" }
+        "##;
+        let file: ThemeFile = toml::from_str(content).unwrap();
+
+        assert_eq!(file.man.bold, Some(Style::new().red().bold()));
+        assert_eq!(file.man.underline, None);
+    }
+
+    #[test]
+    fn test_secondary_defaults_to_accent() {
+        let content = r##"
+        foreground = "white"
+        background = "black"
+        muted = "gray"
+        accent = "#f89a63"
+        "##;
+        let file: ThemeFile = toml::from_str(content).unwrap();
+
+        assert_eq!(file.colors.secondary, file.colors.accent);
+    }
+}